@@ -0,0 +1,20 @@
+mod store;
+
+use proc_macro::TokenStream;
+
+/// Generates field accessors for `cuite_reactive::Store`.
+///
+/// For a struct `Foo`, a trait `FooStoreFields` is generated with a method per
+/// field, implemented for both `Store<Foo>` and `StoreField<_, Foo>`. Fields
+/// named like the methods of those types, e.g. `get` or `set`, are rejected.
+///
+/// The trait has the visibility of the struct. Accessors of less visible
+/// fields are put into `FooPrivateStoreFields`, `FooSuperStoreFields` or
+/// `FooCrateStoreFields` instead, which have the visibility of those fields.
+#[proc_macro_derive(Store)]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    store::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,209 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Visibility};
+
+/// Methods of `Store` and `StoreField`, and of the traits implemented for
+/// them, which would shadow the accessors of fields with the same names or make
+/// calling them ambiguous: inherent methods and methods taking `self` by value
+/// take precedence over the ones of the generated trait.
+const RESERVED_NAMES: &[&str] = &[
+    // inherent methods
+    "field",
+    "get",
+    "get_untracked",
+    "root",
+    "set",
+    "set_untracked",
+    "track",
+    "update",
+    "update_untracked",
+    "with",
+    "with_untracked",
+    // `Clone`, `Debug` and blanket implementations
+    "borrow",
+    "borrow_mut",
+    "clone",
+    "clone_from",
+    "clone_into",
+    "fmt",
+    "into",
+    "to_owned",
+    "try_into",
+    "type_id",
+];
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic stores are not supported",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "only structs with named fields are supported",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "only structs with named fields are supported",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+
+    // Accessors of fields less visible than the struct go to separate traits
+    // with the visibility of those fields, so that they don't leak the fields.
+    let mut groups = vec![FieldGroup::new(
+        input.vis.clone(),
+        format_ident!("{}StoreFields", ident),
+    )];
+
+    for (index, field) in fields.iter().enumerate() {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let index = index as u32;
+
+        if RESERVED_NAMES.iter().any(|v| name == *v) {
+            return Err(Error::new_spanned(
+                name,
+                format!("field name `{name}` is reserved by store methods"),
+            ));
+        }
+
+        let group = match is_at_least_as_visible(&field.vis, &input.vis) {
+            true => &mut groups[0],
+            false => {
+                let prefix = match rank(&field.vis) {
+                    Some(0) => "Private",
+                    Some(1) => "Super",
+                    Some(2) => "Crate",
+                    _ => {
+                        return Err(Error::new_spanned(
+                            &field.vis,
+                            "`pub(in ..)` fields must have the visibility of the struct",
+                        ))
+                    }
+                };
+
+                let trait_ident = format_ident!("{}{}StoreFields", ident, prefix);
+                match groups.iter().position(|v| v.trait_ident == trait_ident) {
+                    Some(i) => &mut groups[i],
+                    None => {
+                        groups.push(FieldGroup::new(field.vis.clone(), trait_ident));
+                        groups.last_mut().unwrap()
+                    }
+                }
+            }
+        };
+
+        let trait_ident = &group.trait_ident;
+
+        group.trait_methods.push(quote! {
+            fn #name(&self) -> ::cuite_reactive::StoreField<R, #ty>;
+        });
+
+        group.field_impls.push(quote! {
+            fn #name(&self) -> ::cuite_reactive::StoreField<R, #ty> {
+                self.field(#index, |v| &v.#name, |v| &mut v.#name)
+            }
+        });
+
+        group.store_impls.push(quote! {
+            fn #name(&self) -> ::cuite_reactive::StoreField<#ident, #ty> {
+                #trait_ident::#name(&self.root())
+            }
+        });
+    }
+
+    let traits = groups.iter().map(|group| {
+        let FieldGroup {
+            vis,
+            trait_ident,
+            trait_methods,
+            field_impls,
+            store_impls,
+        } = group;
+
+        quote! {
+            #vis trait #trait_ident<R> {
+                #(#trait_methods)*
+            }
+
+            impl<R: ::cuite_reactive::Value> #trait_ident<R> for ::cuite_reactive::StoreField<R, #ident> {
+                #(#field_impls)*
+            }
+
+            impl #trait_ident<#ident> for ::cuite_reactive::Store<#ident> {
+                #(#store_impls)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#traits)*
+    })
+}
+
+/// Accessors of the fields with the same visibility, generated as one trait.
+struct FieldGroup {
+    vis: Visibility,
+    trait_ident: Ident,
+    trait_methods: Vec<TokenStream>,
+    field_impls: Vec<TokenStream>,
+    store_impls: Vec<TokenStream>,
+}
+
+impl FieldGroup {
+    fn new(vis: Visibility, trait_ident: Ident) -> FieldGroup {
+        FieldGroup {
+            vis,
+            trait_ident,
+            trait_methods: Vec::new(),
+            field_impls: Vec::new(),
+            store_impls: Vec::new(),
+        }
+    }
+}
+
+/// Returns the rank of a visibility, from private to public, or `None` for
+/// `pub(in ..)`, which can't be compared without resolving the path.
+fn rank(vis: &Visibility) -> Option<u8> {
+    match vis {
+        Visibility::Inherited => Some(0),
+        Visibility::Restricted(vis) if vis.in_token.is_none() => {
+            if vis.path.is_ident("self") {
+                Some(0)
+            } else if vis.path.is_ident("super") {
+                Some(1)
+            } else if vis.path.is_ident("crate") {
+                Some(2)
+            } else {
+                None
+            }
+        }
+        Visibility::Restricted(_) => None,
+        Visibility::Public(_) => Some(3),
+    }
+}
+
+fn is_at_least_as_visible(field: &Visibility, store: &Visibility) -> bool {
+    if quote!(#field).to_string() == quote!(#store).to_string() {
+        return true;
+    }
+
+    match (rank(field), rank(store)) {
+        (Some(field), Some(store)) => field >= store,
+        // `pub(in ..)` is never wider than `pub(crate)`
+        (Some(field), None) => field >= 2,
+        (None, _) => false,
+    }
+}
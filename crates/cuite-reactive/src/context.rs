@@ -0,0 +1,31 @@
+use std::any::TypeId;
+
+use crate::node::{wrap_value, Value};
+use crate::runtime::with_runtime;
+
+/// Provides a value to the current scope and all of its descendants.
+///
+/// Providing a value of the same type again replaces the previous one.
+pub fn provide_context<T: Value>(value: T) {
+    let value = wrap_value(value);
+    with_runtime(|runtime| runtime.provide_context(TypeId::of::<T>(), value));
+}
+
+/// Returns a value of the given type provided by the nearest ancestor of the
+/// current scope, or `None` if there's none.
+pub fn use_context<T: Clone + Value>() -> Option<T> {
+    let value = with_runtime(|runtime| runtime.get_context(TypeId::of::<T>()))?;
+    let borrow = value.borrow();
+    borrow.downcast_ref::<T>().cloned()
+}
+
+/// Same as [`use_context`], but panics if the value is not provided.
+pub fn expect_context<T: Clone + Value>() -> T {
+    match use_context() {
+        Some(value) => value,
+        None => panic!(
+            "context of type {} is not provided",
+            std::any::type_name::<T>()
+        ),
+    }
+}
@@ -1,32 +1,126 @@
-use crate::node::{wrap_effect_computation, wrap_value, NodeId};
+use std::panic::Location;
+
+use crate::node::{wrap_effect_computation, wrap_value, NodeId, Value};
 use crate::runtime::with_runtime;
 
+/// Creates an effect run in the [`EffectPhase::Render`] phase.
+#[track_caller]
 pub fn create_effect<T, F>(func: F) -> Effect
 where
-    T: 'static,
+    T: Value,
     F: 'static + Fn(Option<T>) -> T,
 {
     Effect::new(func)
 }
 
+/// Creates an effect run in the given phase.
+#[track_caller]
+pub fn create_effect_with_phase<T, F>(phase: EffectPhase, func: F) -> Effect
+where
+    T: Value,
+    F: 'static + Fn(Option<T>) -> T,
+{
+    Effect::new_with_phase(phase, func)
+}
+
+/// Creates an effect run in the [`EffectPhase::PreLayout`] phase.
+#[track_caller]
+pub fn create_pre_layout_effect<T, F>(func: F) -> Effect
+where
+    T: Value,
+    F: 'static + Fn(Option<T>) -> T,
+{
+    Effect::new_with_phase(EffectPhase::PreLayout, func)
+}
+
+/// Creates an effect run in the [`EffectPhase::Layout`] phase.
+#[track_caller]
+pub fn create_layout_effect<T, F>(func: F) -> Effect
+where
+    T: Value,
+    F: 'static + Fn(Option<T>) -> T,
+{
+    Effect::new_with_phase(EffectPhase::Layout, func)
+}
+
+/// Creates an effect run in the [`EffectPhase::Render`] phase, same as
+/// [`create_effect`].
+#[track_caller]
+pub fn create_render_effect<T, F>(func: F) -> Effect
+where
+    T: Value,
+    F: 'static + Fn(Option<T>) -> T,
+{
+    Effect::new_with_phase(EffectPhase::Render, func)
+}
+
+/// Creates an effect run in the [`EffectPhase::Idle`] phase.
+#[track_caller]
+pub fn create_idle_effect<T, F>(func: F) -> Effect
+where
+    T: Value,
+    F: 'static + Fn(Option<T>) -> T,
+{
+    Effect::new_with_phase(EffectPhase::Idle, func)
+}
+
+/// Phase in which a scheduled effect is run.
+///
+/// Pending effects are run phase by phase, in the order of the variants.
+/// The first run of an effect happens immediately on creation, regardless of
+/// its phase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EffectPhase {
+    /// Effects which have to be run before the layout, e.g. updating styles.
+    PreLayout,
+    /// Effects affecting the layout, e.g. updating sizes of widgets.
+    Layout,
+    /// Effects updating what's displayed to the user.
+    #[default]
+    Render,
+    /// Background work which isn't visible to the user.
+    Idle,
+}
+
+impl EffectPhase {
+    pub(crate) const COUNT: usize = 4;
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Effect {
-    _id: NodeId,
+    id: NodeId,
 }
 
 impl Effect {
+    #[track_caller]
     pub fn new<T, F>(func: F) -> Effect
     where
-        T: 'static,
+        T: Value,
         F: 'static + Fn(Option<T>) -> T,
     {
+        Effect::new_with_phase(EffectPhase::default(), func)
+    }
+
+    #[track_caller]
+    pub fn new_with_phase<T, F>(phase: EffectPhase, func: F) -> Effect
+    where
+        T: Value,
+        F: 'static + Fn(Option<T>) -> T,
+    {
+        let location = Location::caller();
         let value = wrap_value(None::<T>);
         let computation = wrap_effect_computation(func);
         let id = with_runtime(|runtime| {
-            let id = runtime.create_effect(value, computation);
+            let id = runtime.create_effect(value, computation, phase);
+            runtime.set_node_location(id, location);
             runtime.update_if_necessary(id);
             id
         });
-        Effect { _id: id }
+        Effect { id }
+    }
+
+    /// Returns the id of the effect node, re-run when its sources change.
+    pub fn id(&self) -> NodeId {
+        self.id
     }
 }
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::node::NodeId;
+use crate::runtime::RuntimeInner;
+
+/// Error which occurs when accessing a reactive value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactiveError {
+    /// The node has already been disposed.
+    Disposed { id: NodeId, type_name: &'static str },
+
+    /// The node's value is not of the expected type.
+    TypeMismatch { id: NodeId, expected: &'static str },
+
+    /// The node's value is already borrowed, e.g. when a signal is modified
+    /// from inside of its own `with` closure.
+    ReentrantBorrow { id: NodeId, type_name: &'static str },
+
+    /// The node is owned by a runtime other than the current one.
+    RuntimeMismatch { id: NodeId, type_name: &'static str },
+}
+
+impl ReactiveError {
+    /// Returns the error for a node which is missing from the runtime, either
+    /// because it was disposed, or because it's owned by another runtime.
+    pub(crate) fn missing<T>(runtime: &RuntimeInner, id: NodeId) -> ReactiveError {
+        match runtime.owns(id) {
+            true => ReactiveError::disposed::<T>(id),
+            false => ReactiveError::RuntimeMismatch {
+                id,
+                type_name: std::any::type_name::<T>(),
+            },
+        }
+    }
+
+    pub(crate) fn disposed<T>(id: NodeId) -> ReactiveError {
+        ReactiveError::Disposed {
+            id,
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+
+    pub(crate) fn type_mismatch<T>(id: NodeId) -> ReactiveError {
+        ReactiveError::TypeMismatch {
+            id,
+            expected: std::any::type_name::<T>(),
+        }
+    }
+
+    pub(crate) fn reentrant_borrow<T>(id: NodeId) -> ReactiveError {
+        ReactiveError::ReentrantBorrow {
+            id,
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+impl fmt::Display for ReactiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReactiveError::Disposed { id, type_name } => {
+                write!(f, "node {id:?} of type {type_name} has been disposed")
+            }
+            ReactiveError::TypeMismatch { id, expected } => {
+                write!(f, "node {id:?} does not have a value of type {expected}")
+            }
+            ReactiveError::ReentrantBorrow { id, type_name } => {
+                write!(
+                    f,
+                    "value of node {id:?} of type {type_name} is already borrowed"
+                )
+            }
+            ReactiveError::RuntimeMismatch { id, type_name } => {
+                write!(
+                    f,
+                    "node {id:?} of type {type_name} is owned by a different runtime"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReactiveError {}
@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use slotmap::SlotMap;
+
+use crate::runtime::with_runtime;
+
+/// Boxed future which is not required to be `Send`.
+pub type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Executor of futures spawned by the reactive system, e.g. by resources.
+///
+/// Futures are spawned from the thread of the runtime, and are expected to be
+/// polled on that same thread.
+pub trait Executor {
+    fn spawn_local(&self, future: LocalFuture);
+}
+
+/// Sets the executor used for spawning futures.
+pub fn set_executor(executor: impl Executor + 'static) {
+    with_runtime(|runtime| runtime.set_executor(Rc::new(executor)));
+}
+
+/// Spawns a future on the current executor.
+///
+/// # Panics
+///
+/// Panics if no executor was set with [`set_executor`].
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    with_runtime(|runtime| runtime.spawn_local(Box::pin(future)));
+}
+
+slotmap::new_key_type! {
+    struct TaskId;
+}
+
+/// Deterministic single-threaded executor, useful for tests.
+///
+/// Futures are only polled when [`TestExecutor::run_until_stalled`] is
+/// called, in the order they were spawned or woken.
+#[derive(Clone, Default)]
+pub struct TestExecutor {
+    inner: Rc<TestExecutorInner>,
+}
+
+#[derive(Default)]
+struct TestExecutorInner {
+    /// Spawned tasks. A task is `None` while it's being polled.
+    tasks: RefCell<SlotMap<TaskId, Option<LocalFuture>>>,
+
+    /// Queue of tasks that should be polled.
+    ready: Arc<Mutex<VecDeque<TaskId>>>,
+}
+
+struct TaskWaker {
+    id: TaskId,
+    ready: Arc<Mutex<VecDeque<TaskId>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.id);
+    }
+}
+
+impl TestExecutor {
+    pub fn new() -> TestExecutor {
+        TestExecutor::default()
+    }
+
+    /// Polls the tasks until none of them can make progress.
+    pub fn run_until_stalled(&self) {
+        loop {
+            let id = self.inner.ready.lock().unwrap().pop_front();
+            let Some(id) = id else {
+                break;
+            };
+
+            let task = self
+                .inner
+                .tasks
+                .borrow_mut()
+                .get_mut(id)
+                .and_then(Option::take);
+            let Some(mut task) = task else {
+                // the task has completed, or has been woken multiple times
+                continue;
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                ready: self.inner.ready.clone(),
+            }));
+
+            // the task is polled without borrowing `tasks`, since it can spawn
+            // other tasks
+            let poll = task.as_mut().poll(&mut Context::from_waker(&waker));
+
+            let mut tasks = self.inner.tasks.borrow_mut();
+            match poll {
+                Poll::Ready(()) => {
+                    tasks.remove(id);
+                }
+                Poll::Pending => {
+                    tasks[id] = Some(task);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of tasks which haven't completed yet.
+    pub fn pending_tasks(&self) -> usize {
+        self.inner.tasks.borrow().len()
+    }
+}
+
+impl Executor for TestExecutor {
+    fn spawn_local(&self, future: LocalFuture) {
+        let id = self.inner.tasks.borrow_mut().insert(Some(future));
+        self.inner.ready.lock().unwrap().push_back(id);
+    }
+}
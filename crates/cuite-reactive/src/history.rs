@@ -0,0 +1,393 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+use ahash::AHashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ReactiveError;
+use crate::node::{NodeId, Value};
+use crate::runtime::{with_runtime, Runtime};
+use crate::signal::Signal;
+
+/// Values of the signals registered in a [`Recorder`], by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    values: BTreeMap<String, serde_json::Value>,
+}
+
+impl Snapshot {
+    /// Returns the serialized value of the signal with the given name.
+    pub fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.values.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &serde_json::Value)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+/// Write of a registered signal, recorded by a [`Recorder`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Name the signal was registered with.
+    pub signal: String,
+
+    /// Serialized value written to the signal.
+    pub value: serde_json::Value,
+}
+
+/// Error which occurs when writing a recorded value back into a signal.
+#[derive(Debug)]
+pub enum HistoryError {
+    /// No signal is registered with the given name.
+    UnknownSignal { name: String },
+
+    /// The recorded value can't be deserialized into the type of the signal.
+    Deserialize {
+        name: String,
+        error: serde_json::Error,
+    },
+
+    /// The signal can't be written, e.g. because it has been disposed.
+    Reactive(ReactiveError),
+}
+
+impl From<ReactiveError> for HistoryError {
+    fn from(err: ReactiveError) -> HistoryError {
+        HistoryError::Reactive(err)
+    }
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::UnknownSignal { name } => {
+                write!(f, "no signal is registered as {name:?}")
+            }
+            HistoryError::Deserialize { name, error } => {
+                write!(f, "invalid value for signal {name:?}: {error}")
+            }
+            HistoryError::Reactive(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HistoryError::UnknownSignal { .. } => None,
+            HistoryError::Deserialize { error, .. } => Some(error),
+            HistoryError::Reactive(err) => Some(err),
+        }
+    }
+}
+
+/// Type-erased access to a registered signal.
+struct RecordedSignal {
+    read: Rc<dyn Fn() -> Option<serde_json::Value>>,
+    write: Rc<dyn Fn(serde_json::Value) -> Result<(), HistoryError>>,
+}
+
+struct RecorderInner {
+    runtime: Runtime,
+    capacity: usize,
+
+    signals: RefCell<BTreeMap<String, RecordedSignal>>,
+    names: RefCell<AHashMap<NodeId, String>>,
+
+    /// Values of the signals before the first entry of the history. Entries
+    /// dropped from the front of the history are folded into it.
+    base: RefCell<Snapshot>,
+    history: RefCell<VecDeque<HistoryEntry>>,
+
+    /// Number of entries of the history which are currently applied. It's
+    /// less than the length of the history after stepping back.
+    position: Cell<usize>,
+
+    /// Whether the writes are currently made by the recorder itself, and
+    /// shouldn't be recorded.
+    traveling: Cell<bool>,
+}
+
+/// Records the writes of opt-in signals with serializable values, for
+/// snapshots and time-travel debugging.
+///
+/// Signals are registered by name, so that snapshots and histories can be
+/// saved and replayed in another run of the program. The last `capacity`
+/// writes are kept: stepping through them with [`Recorder::go_to`] writes the
+/// recorded values back into the signals, without recording them again. A
+/// new write after stepping back discards the undone entries.
+///
+/// Only writes of the runtime which was current when the recorder was created
+/// are recorded. Dropping the last handle of the recorder stops the
+/// recording.
+#[derive(Clone)]
+pub struct Recorder {
+    inner: Rc<RecorderInner>,
+}
+
+impl Recorder {
+    /// Creates a recorder for the current runtime, replacing any previous
+    /// recorder of that runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is zero.
+    pub fn new(capacity: usize) -> Recorder {
+        assert!(capacity > 0, "recorder capacity must be non-zero");
+
+        let inner = Rc::new(RecorderInner {
+            runtime: Runtime::current(),
+            capacity,
+            signals: Default::default(),
+            names: Default::default(),
+            base: Default::default(),
+            history: Default::default(),
+            position: Default::default(),
+            traveling: Default::default(),
+        });
+
+        let weak = Rc::downgrade(&inner);
+        with_runtime(|runtime| {
+            runtime.set_write_hook(Some(Rc::new(move |id| {
+                if let Some(inner) = Weak::upgrade(&weak) {
+                    inner.record(id);
+                }
+            })))
+        });
+
+        Recorder { inner }
+    }
+
+    /// Starts recording the writes of the signal under the given name,
+    /// replacing the signal previously registered with that name.
+    ///
+    /// The current value of the signal is used when stepping back before the
+    /// first recorded write.
+    pub fn register<T>(&self, name: impl Into<String>, signal: Signal<T>)
+    where
+        T: Value + Serialize + DeserializeOwned,
+    {
+        let name = name.into();
+
+        let write_name = name.clone();
+        let recorded = RecordedSignal {
+            read: Rc::new(move || {
+                // values which can't be serialized are skipped, e.g. maps with
+                // non-string keys
+                let value = signal.try_with_untracked(|value| serde_json::to_value(value));
+                match value {
+                    Ok(Ok(value)) => Some(value),
+                    _ => None,
+                }
+            }),
+            write: Rc::new(move |value| {
+                let value = serde_json::from_value::<T>(value).map_err(|error| {
+                    HistoryError::Deserialize {
+                        name: write_name.clone(),
+                        error,
+                    }
+                })?;
+                signal.try_set(value)?;
+                Ok(())
+            }),
+        };
+
+        if let Some(value) = self.inner.runtime.enter(|| (recorded.read)()) {
+            let mut base = self.inner.base.borrow_mut();
+            base.values.insert(name.clone(), value);
+        }
+
+        let mut names = self.inner.names.borrow_mut();
+        names.retain(|_, registered| *registered != name);
+        names.insert(signal.id(), name.clone());
+        self.inner.signals.borrow_mut().insert(name, recorded);
+    }
+
+    /// Returns the current values of the registered signals.
+    pub fn snapshot(&self) -> Snapshot {
+        let signals = self.inner.signals.borrow();
+        let values = self.inner.runtime.enter(|| {
+            signals
+                .iter()
+                .filter_map(|(name, signal)| Some((name.clone(), (signal.read)()?)))
+                .collect()
+        });
+        Snapshot { values }
+    }
+
+    /// Writes the values of the snapshot back into the signals, in a single
+    /// batch. The writes are recorded, so that they can be undone.
+    pub fn restore(&self, snapshot: &Snapshot) -> Result<(), HistoryError> {
+        self.inner.apply(&snapshot.values)
+    }
+
+    /// Returns the recorded writes, oldest first, including the ones undone
+    /// by stepping back.
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        self.inner.history.borrow().iter().cloned().collect()
+    }
+
+    /// Writes the values of the entries into the signals one by one, as if
+    /// they were set by the program. The writes are recorded.
+    ///
+    /// Nothing is written if one of the entries refers to an unknown signal.
+    pub fn replay(&self, entries: &[HistoryEntry]) -> Result<(), HistoryError> {
+        // the writes run effects, which may register signals
+        let writes = {
+            let signals = self.inner.signals.borrow();
+            entries
+                .iter()
+                .map(|entry| {
+                    let signal =
+                        signals
+                            .get(&entry.signal)
+                            .ok_or_else(|| HistoryError::UnknownSignal {
+                                name: entry.signal.clone(),
+                            })?;
+                    Ok((signal.write.clone(), entry.value.clone()))
+                })
+                .collect::<Result<Vec<_>, HistoryError>>()?
+        };
+
+        for (write, value) in writes {
+            self.inner.runtime.enter(|| write(value))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of recorded writes.
+    pub fn len(&self) -> usize {
+        self.inner.history.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of recorded writes which are currently applied.
+    pub fn position(&self) -> usize {
+        self.inner.position.get()
+    }
+
+    /// Sets the signals to the values they had after the given number of
+    /// recorded writes, in a single batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is greater than the number of recorded writes.
+    pub fn go_to(&self, position: usize) -> Result<(), HistoryError> {
+        let len = self.len();
+        assert!(
+            position <= len,
+            "history position {position} is out of range for {len} entries"
+        );
+
+        let mut values = self.inner.base.borrow().values.clone();
+        for entry in self.inner.history.borrow().range(..position) {
+            values.insert(entry.signal.clone(), entry.value.clone());
+        }
+
+        // resume recording even if a write panics
+        struct EndTravel<'a>(&'a Cell<bool>);
+
+        impl Drop for EndTravel<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+
+        self.inner.traveling.set(true);
+        let end_travel = EndTravel(&self.inner.traveling);
+        let result = self.inner.apply(&values);
+        drop(end_travel);
+
+        self.inner.position.set(position);
+        result
+    }
+
+    /// Undoes the last applied write. Returns `false` if there is nothing to
+    /// undo.
+    pub fn step_back(&self) -> Result<bool, HistoryError> {
+        match self.position() {
+            0 => Ok(false),
+            position => self.go_to(position - 1).map(|_| true),
+        }
+    }
+
+    /// Redoes the next undone write. Returns `false` if there is nothing to
+    /// redo.
+    pub fn step_forward(&self) -> Result<bool, HistoryError> {
+        match self.position() {
+            position if position == self.len() => Ok(false),
+            position => self.go_to(position + 1).map(|_| true),
+        }
+    }
+}
+
+impl RecorderInner {
+    fn record(&self, id: NodeId) {
+        if self.traveling.get() {
+            return;
+        }
+
+        let names = self.names.borrow();
+        let Some(name) = names.get(&id) else {
+            return;
+        };
+        let signals = self.signals.borrow();
+        let Some(value) = (signals[name].read)() else {
+            return;
+        };
+
+        let mut history = self.history.borrow_mut();
+        history.truncate(self.position.get());
+        history.push_back(HistoryEntry {
+            signal: name.clone(),
+            value,
+        });
+
+        if history.len() > self.capacity {
+            let entry = history.pop_front().unwrap();
+            self.base
+                .borrow_mut()
+                .values
+                .insert(entry.signal, entry.value);
+        }
+        self.position.set(history.len());
+    }
+
+    /// Writes the values which differ from the current ones, in a batch.
+    fn apply(&self, values: &BTreeMap<String, serde_json::Value>) -> Result<(), HistoryError> {
+        self.runtime.enter(|| {
+            crate::batch(|| {
+                for (name, value) in values {
+                    let signals = self.signals.borrow();
+                    let signal = signals
+                        .get(name)
+                        .ok_or_else(|| HistoryError::UnknownSignal { name: name.clone() })?;
+
+                    if (signal.read)().as_ref() != Some(value) {
+                        (signal.write)(value.clone())?;
+                    }
+                }
+                Ok(())
+            })
+        })
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("signals", &self.inner.signals.borrow().keys())
+            .field("position", &self.position())
+            .field("len", &self.len())
+            .finish()
+    }
+}
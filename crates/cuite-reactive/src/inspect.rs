@@ -0,0 +1,147 @@
+use std::fmt::Write;
+use std::panic::Location;
+
+use ahash::AHashMap;
+
+use crate::node::{NodeId, NodeKind, NodeState};
+
+/// Snapshot of a reactive node and its relationships, returned by
+/// [`Runtime::nodes`](crate::Runtime::nodes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo {
+    pub id: NodeId,
+    pub kind: NodeType,
+    pub state: NodeState,
+
+    /// Length of the longest path from a signal to the node, as of the last
+    /// run of the node.
+    pub height: usize,
+
+    /// Where the node was created, if it was created by one of the public
+    /// constructors.
+    pub location: Option<&'static Location<'static>>,
+
+    /// Owner of the node.
+    pub parent: Option<NodeId>,
+
+    /// Nodes owned by the node.
+    pub children: Vec<NodeId>,
+
+    /// Nodes read by the node during its last run, in the order they were
+    /// first read.
+    pub sources: Vec<NodeId>,
+
+    /// Nodes which are updated when the node changes.
+    pub subscribers: Vec<NodeId>,
+}
+
+/// Number of live nodes of a runtime, returned by
+/// [`Runtime::stats`](crate::Runtime::stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeStats {
+    pub scopes: usize,
+    pub signals: usize,
+    pub triggers: usize,
+    pub effects: usize,
+    pub memos: usize,
+
+    /// Number of source-subscriber dependencies.
+    pub edges: usize,
+
+    /// Number of effects scheduled, but not run yet.
+    pub pending_effects: usize,
+}
+
+/// Kind of a reactive node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeType {
+    Scope,
+    Signal,
+    Trigger,
+    Effect,
+    Memo,
+}
+
+impl NodeType {
+    pub(crate) fn of(kind: &NodeKind) -> NodeType {
+        match kind {
+            NodeKind::Scope => NodeType::Scope,
+            NodeKind::Signal { .. } => NodeType::Signal,
+            NodeKind::Trigger => NodeType::Trigger,
+            NodeKind::Effect { .. } => NodeType::Effect,
+            NodeKind::Memo { .. } => NodeType::Memo,
+        }
+    }
+
+    fn shape(self) -> &'static str {
+        match self {
+            NodeType::Scope => "folder",
+            NodeType::Signal | NodeType::Trigger => "ellipse",
+            NodeType::Effect => "box",
+            NodeType::Memo => "diamond",
+        }
+    }
+}
+
+/// Formats the nodes as a Graphviz graph.
+pub(crate) fn to_dot(nodes: &[NodeInfo]) -> String {
+    let names = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id, format!("n{i}")))
+        .collect::<AHashMap<_, _>>();
+
+    let mut out = String::from("digraph {\n");
+
+    for node in nodes {
+        let mut label = format!("{:?}", node.kind);
+        if node.state != NodeState::Clean {
+            let _ = write!(label, " ({:?})", node.state);
+        }
+        if let Some(location) = node.location {
+            let _ = write!(label, "\n{location}");
+        }
+
+        let _ = writeln!(
+            out,
+            "    {} [label=\"{}\", shape={}];",
+            names[&node.id],
+            escape(&label),
+            node.kind.shape()
+        );
+    }
+
+    for node in nodes {
+        for subscriber in &node.subscribers {
+            if let Some(subscriber) = names.get(subscriber) {
+                let _ = writeln!(out, "    {} -> {subscriber};", names[&node.id]);
+            }
+        }
+
+        for child in &node.children {
+            if let Some(child) = names.get(child) {
+                let _ = writeln!(
+                    out,
+                    "    {} -> {child} [style=dashed, color=gray];",
+                    names[&node.id]
+                );
+            }
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn escape(label: &str) -> String {
+    let mut out = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out
+}
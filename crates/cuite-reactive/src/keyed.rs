@@ -0,0 +1,295 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::panic::Location;
+
+use ahash::AHashMap;
+
+use crate::effect::create_effect;
+use crate::node::{NodeId, Value};
+use crate::runtime::with_runtime;
+use crate::signal_vec::{SignalVec, VecDiff};
+
+/// Creates a list of children from a reactive iterable, reconciled by key.
+///
+/// Every time `each` changes, the new keys are diffed against the old ones
+/// with [`diff_keyed`], so that a child is only constructed for an added key,
+/// and only disposed for a removed key. Each child is constructed untracked,
+/// inside of its own scope, which is disposed together with the child.
+///
+/// The returned vector records the minimal changes, so that a view can apply
+/// them to its own children incrementally. Rows with duplicate keys are
+/// supported, and are matched in the order of occurrence, see [`diff_keyed`].
+#[track_caller]
+pub fn create_keyed<T, K, V, I>(
+    each: impl Fn() -> I + 'static,
+    key: impl Fn(&T) -> K + 'static,
+    child: impl Fn(T) -> V + 'static,
+) -> SignalVec<V>
+where
+    I: IntoIterator<Item = T>,
+    T: 'static,
+    K: Clone + Eq + Hash + 'static,
+    V: Clone + Value,
+{
+    let location = Location::caller();
+    let owner = with_runtime(|runtime| {
+        let owner = runtime.create_scope();
+        runtime.set_node_location(owner, location);
+        owner
+    });
+    let output = SignalVec::new(Vec::new());
+
+    // keys and scopes of the current rows
+    let rows = RefCell::new(Vec::<(K, NodeId)>::new());
+
+    create_effect(move |_| {
+        let mut items = each().into_iter().map(Some).collect::<Vec<_>>();
+        let keys = items
+            .iter()
+            .map(|item| key(item.as_ref().unwrap()))
+            .collect::<Vec<_>>();
+
+        with_runtime(|runtime| {
+            runtime.untrack(|| {
+                let old_keys = rows
+                    .borrow()
+                    .iter()
+                    .map(|(k, _)| k.clone())
+                    .collect::<Vec<_>>();
+                let ops = diff_keyed(&old_keys, &keys);
+
+                runtime.batch(|| {
+                    for op in ops {
+                        match op {
+                            KeyedOp::Remove { index } => {
+                                let (_, scope) = rows.borrow_mut().remove(index);
+                                runtime.dispose_node(scope);
+                                output.apply(VecDiff::Remove { index });
+                            }
+                            KeyedOp::Insert { index, new_index } => {
+                                let item = items[new_index].take().unwrap();
+                                let (scope, value) = runtime.with_scope(Some(owner), || {
+                                    let scope = runtime.create_scope();
+                                    let value = runtime.with_scope(Some(scope), || child(item));
+                                    (scope, value)
+                                });
+
+                                let key = keys[new_index].clone();
+                                rows.borrow_mut().insert(index, (key, scope));
+                                output.apply(VecDiff::Insert { index, value });
+                            }
+                            KeyedOp::Move { from, to } => {
+                                let mut rows = rows.borrow_mut();
+                                let row = rows.remove(from);
+                                rows.insert(to, row);
+                                drop(rows);
+
+                                output.apply(VecDiff::Move { from, to });
+                            }
+                        }
+                    }
+                });
+            })
+        });
+    });
+
+    output
+}
+
+/// Operation produced by [`diff_keyed`].
+///
+/// Indices refer to the state of the list right before the operation is
+/// applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyedOp {
+    /// Remove the item at `index`.
+    Remove { index: usize },
+
+    /// Insert the item `new[new_index]` at `index`.
+    Insert { index: usize, new_index: usize },
+
+    /// Move the item from `from` to `to`.
+    Move { from: usize, to: usize },
+}
+
+/// Computes the operations which transform the list of `old` keys into the
+/// list of `new` keys.
+///
+/// Items which are not present in `new` are removed, and those not present in
+/// `old` are inserted. Retained items forming the longest increasing
+/// subsequence stay in place, so the number of moves is minimal. Runs in
+/// `O(n log n)`.
+///
+/// Duplicate keys are matched in the order of occurrence: the k-th occurrence
+/// of a key in `old` is matched with the k-th occurrence in `new`, and the
+/// occurrences without a counterpart are removed or inserted.
+pub fn diff_keyed<K: Eq + Hash>(old: &[K], new: &[K]) -> Vec<KeyedOp> {
+    // indices in `new` of every key, in the order of occurrence
+    let mut new_indices = AHashMap::<_, VecDeque<_>>::with_capacity(new.len());
+    for (i, key) in new.iter().enumerate() {
+        new_indices.entry(key).or_default().push_back(i);
+    }
+
+    // index in `new` of every item in `old`, if it's retained
+    let matches = old
+        .iter()
+        .map(|key| new_indices.get_mut(key)?.pop_front())
+        .collect::<Vec<_>>();
+
+    let mut ops = Vec::new();
+
+    // remove from the end, so that the indices stay valid
+    for (index, i) in matches.iter().enumerate().rev() {
+        if i.is_none() {
+            ops.push(KeyedOp::Remove { index });
+        }
+    }
+
+    // retained items, as indices into `new`
+    let current = matches.into_iter().flatten().collect::<Vec<_>>();
+
+    let mut old_positions = vec![None; new.len()];
+    for (position, &i) in current.iter().enumerate() {
+        old_positions[i] = Some(position);
+    }
+
+    let mut stable = vec![false; new.len()];
+    for i in longest_increasing_subsequence(&current) {
+        stable[current[i]] = true;
+    }
+
+    // Every other item is placed right before its successor, going from the
+    // end. So it ends up in a run of such items, right before the next stable
+    // item (the anchor of the run) or at the end of the list, and nothing is
+    // ever placed inside of a run. This gives every item a fixed slot, and the
+    // index of an item is the number of occupied slots before its slot.
+    let end = current.len();
+    let mut anchors = vec![end; new.len()];
+    let mut run_starts = vec![0; end + 1];
+    let mut run_lens = vec![0; end + 1];
+
+    let mut anchor = end;
+    for i in (0..new.len()).rev() {
+        match (stable[i], old_positions[i]) {
+            (true, Some(position)) => anchor = position,
+            _ => {
+                anchors[i] = anchor;
+                run_starts[anchor] = i;
+                run_lens[anchor] += 1;
+            }
+        }
+    }
+
+    // the slots of a run come right before the slot of its anchor
+    let mut run_slots = Vec::with_capacity(end + 1);
+    let mut len = 0;
+    for run_len in &run_lens {
+        run_slots.push(len);
+        len += run_len + 1;
+    }
+
+    let old_slot = |position: usize| run_slots[position] + run_lens[position];
+    let new_slot = |i: usize| run_slots[anchors[i]] + i - run_starts[anchors[i]];
+
+    let mut slots = Slots::new(len);
+    for position in 0..end {
+        slots.insert(old_slot(position));
+    }
+
+    for i in (0..new.len()).rev() {
+        if stable[i] {
+            continue;
+        }
+
+        if let Some(position) = old_positions[i] {
+            let from = slots.count_before(old_slot(position));
+            slots.remove(old_slot(position));
+            let to = slots.count_before(new_slot(i));
+            slots.insert(new_slot(i));
+
+            if from != to {
+                ops.push(KeyedOp::Move { from, to });
+            }
+        } else {
+            let index = slots.count_before(new_slot(i));
+            slots.insert(new_slot(i));
+            ops.push(KeyedOp::Insert {
+                index,
+                new_index: i,
+            });
+        }
+    }
+
+    ops
+}
+
+/// Set of occupied slots, which counts the occupied slots before a slot in
+/// `O(log n)` (a Fenwick tree).
+struct Slots {
+    tree: Vec<usize>,
+}
+
+impl Slots {
+    fn new(len: usize) -> Slots {
+        Slots {
+            tree: vec![0; len + 1],
+        }
+    }
+
+    fn insert(&mut self, slot: usize) {
+        let mut i = slot + 1;
+        while i < self.tree.len() {
+            self.tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn remove(&mut self, slot: usize) {
+        let mut i = slot + 1;
+        while i < self.tree.len() {
+            self.tree[i] -= 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn count_before(&self, slot: usize) -> usize {
+        let mut count = 0;
+        let mut i = slot;
+        while i > 0 {
+            count += self.tree[i];
+            i &= i - 1;
+        }
+        count
+    }
+}
+
+/// Returns the indices of the longest strictly increasing subsequence.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // tails[k] is the index of the smallest tail of a subsequence of length k+1
+    let mut tails = Vec::<usize>::new();
+    let mut predecessors = vec![usize::MAX; values.len()];
+
+    for (i, &value) in values.iter().enumerate() {
+        let pos = tails.partition_point(|&j| values[j] < value);
+        if pos > 0 {
+            predecessors[i] = tails[pos - 1];
+        }
+
+        if pos == tails.len() {
+            tails.push(i);
+        } else {
+            tails[pos] = i;
+        }
+    }
+
+    let mut result = Vec::with_capacity(tails.len());
+    let mut current = tails.last().copied().unwrap_or(usize::MAX);
+    while current != usize::MAX {
+        result.push(current);
+        current = predecessors[current];
+    }
+
+    result.reverse();
+    result
+}
@@ -1,7 +1,51 @@
+mod context;
 mod effect;
+mod error;
+mod executor;
+#[cfg(feature = "serde")]
+mod history;
+mod inspect;
+mod keyed;
+mod memo;
 mod node;
+mod resource;
 mod runtime;
+mod scope;
+mod selector;
 mod signal;
+mod signal_map;
+mod signal_vec;
+mod store;
+mod traits;
+mod trigger;
 
-pub use self::effect::{create_effect, Effect};
-pub use self::signal::{create_signal, Signal};
+pub use cuite_reactive_macros::Store;
+
+pub use self::context::{expect_context, provide_context, use_context};
+pub use self::effect::{
+    create_effect, create_effect_with_phase, create_idle_effect, create_layout_effect,
+    create_pre_layout_effect, create_render_effect, Effect, EffectPhase,
+};
+pub use self::error::ReactiveError;
+pub use self::executor::{set_executor, spawn_local, Executor, LocalFuture, TestExecutor};
+#[cfg(feature = "serde")]
+pub use self::history::{HistoryEntry, HistoryError, Recorder, Snapshot};
+pub use self::inspect::{NodeInfo, NodeType, RuntimeStats};
+pub use self::keyed::{create_keyed, diff_keyed, KeyedOp};
+pub use self::memo::{create_memo, create_memo_with_options, Memo};
+pub use self::node::{NodeId, NodeState, Value};
+pub use self::resource::{create_resource, Resource, ResourceError};
+pub use self::runtime::{
+    batch, set_max_effect_iterations, untrack, Runtime, RuntimeId, DEFAULT_MAX_EFFECT_ITERATIONS,
+};
+pub use self::scope::{create_root, on_cleanup, Scope};
+pub use self::selector::{create_selector, Selector};
+pub use self::signal::{
+    create_signal, create_signal_split, create_signal_with_options, ReadSignal, Signal,
+    SignalOptions, SignalSender, WriteSignal,
+};
+pub use self::signal_map::{create_signal_map, MapDiff, SignalMap};
+pub use self::signal_vec::{create_signal_vec, SignalVec, VecDiff};
+pub use self::store::{create_store, Store, StoreField};
+pub use self::traits::{SignalGet, SignalSet, SignalUpdate, SignalWith};
+pub use self::trigger::{create_trigger, Trigger};
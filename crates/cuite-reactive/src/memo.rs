@@ -0,0 +1,171 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::rc::Rc;
+
+use crate::error::ReactiveError;
+use crate::node::{wrap_memo_computation, wrap_value, Equality, NodeId, Value};
+use crate::runtime::with_runtime;
+use crate::signal::SignalOptions;
+use crate::traits::SignalWith;
+
+#[track_caller]
+pub fn create_memo<T, F>(func: F) -> Memo<T>
+where
+    T: Value + PartialEq,
+    F: 'static + Fn(Option<&T>) -> T,
+{
+    Memo::new(func)
+}
+
+#[track_caller]
+pub fn create_memo_with_options<T, F>(func: F, options: SignalOptions<T>) -> Memo<T>
+where
+    T: Value,
+    F: 'static + Fn(Option<&T>) -> T,
+{
+    Memo::new_with_options(func, options)
+}
+
+/// Cached derived value.
+///
+/// The computation is run lazily, and by default the subscribers are only
+/// notified when the new value differs from the previous one.
+pub struct Memo<T> {
+    id: NodeId,
+    marker: PhantomData<T>,
+}
+
+impl<T: Value> Memo<T> {
+    #[track_caller]
+    pub fn new<F>(func: F) -> Memo<T>
+    where
+        T: PartialEq,
+        F: 'static + Fn(Option<&T>) -> T,
+    {
+        let equality: Equality<T> = Rc::new(T::eq);
+        Memo::new_with_equality(func, Some(equality))
+    }
+
+    #[track_caller]
+    pub fn new_with_options<F>(func: F, options: SignalOptions<T>) -> Memo<T>
+    where
+        F: 'static + Fn(Option<&T>) -> T,
+    {
+        Memo::new_with_equality(func, options.into_equality())
+    }
+
+    #[track_caller]
+    fn new_with_equality<F>(func: F, equality: Option<Equality<T>>) -> Memo<T>
+    where
+        F: 'static + Fn(Option<&T>) -> T,
+    {
+        let location = Location::caller();
+        let value = wrap_value(None::<T>);
+        let computation = wrap_memo_computation(func, equality);
+        let id = with_runtime(|runtime| {
+            let id = runtime.create_memo(value, computation);
+            runtime.set_node_location(id, location);
+            id
+        });
+        Memo {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the id of the memo node, which caches the computed value.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.track();
+        self.get_untracked()
+    }
+
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.track();
+        self.with_untracked(func)
+    }
+
+    pub fn try_get(&self) -> Result<T, ReactiveError>
+    where
+        T: Clone,
+    {
+        self.track();
+        self.try_with_untracked(T::clone)
+    }
+
+    pub fn try_with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Result<Ret, ReactiveError> {
+        self.track();
+        self.try_with_untracked(func)
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.try_with_untracked(func)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_with_untracked<Ret>(
+        &self,
+        func: impl FnOnce(&T) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        with_runtime(|runtime| {
+            runtime.update_if_necessary(self.id);
+            let value = runtime
+                .get_node_value(self.id)
+                .ok_or_else(|| ReactiveError::missing::<T>(runtime, self.id))?;
+            let borrow = value
+                .try_borrow()
+                .ok_or_else(|| ReactiveError::reentrant_borrow::<T>(self.id))?;
+            let casted = borrow
+                .downcast_ref::<Option<T>>()
+                .ok_or_else(|| ReactiveError::type_mismatch::<T>(self.id))?
+                .as_ref()
+                // the value is taken out while the memo is being computed
+                .ok_or_else(|| ReactiveError::reentrant_borrow::<T>(self.id))?;
+            Ok(func(casted))
+        })
+    }
+
+    pub fn track(&self) {
+        with_runtime(|runtime| runtime.track(self.id));
+    }
+}
+
+impl<T: Value> SignalWith for Memo<T> {
+    type Value = T;
+
+    fn track(&self) {
+        Memo::track(self)
+    }
+
+    fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        Memo::with_untracked(self, func)
+    }
+}
+
+impl<T> fmt::Debug for Memo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Memo({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Memo<T> {}
@@ -1,10 +1,53 @@
 use std::any::Any;
 use std::cell::RefCell;
+use std::fmt;
 use std::marker::PhantomData;
+use std::panic::Location;
 use std::rc::Rc;
 
+use slotmap::Key;
+
+use crate::effect::EffectPhase;
+use crate::runtime::RuntimeId;
+
 slotmap::new_key_type! {
-    pub struct NodeId;
+    /// Key of a node inside of its runtime.
+    pub struct NodeKey;
+}
+
+/// Identifier of a reactive node.
+///
+/// Besides the key of the node, it contains the id of the runtime which owns
+/// the node, so that using a node with a different runtime can be detected.
+///
+/// Signals, memos, effects, triggers and scopes expose their ids with an `id`
+/// method, e.g. for inspecting the nodes with
+/// [`Runtime::node`](crate::Runtime::node).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId {
+    runtime: RuntimeId,
+    key: NodeKey,
+}
+
+impl NodeId {
+    pub(crate) fn new(runtime: RuntimeId, key: NodeKey) -> NodeId {
+        NodeId { runtime, key }
+    }
+
+    /// Returns the id of the runtime which owns the node.
+    pub fn runtime(&self) -> RuntimeId {
+        self.runtime
+    }
+
+    pub(crate) fn key(&self) -> NodeKey {
+        self.key
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeId({:?}@{})", self.key.data(), self.runtime)
+    }
 }
 
 #[derive(Clone)]
@@ -12,26 +55,187 @@ pub struct Node {
     pub value: Option<AnyValue>,
     pub state: NodeState,
     pub kind: NodeKind,
+    /// Length of the longest path from a signal to this node through the
+    /// sources, as of the last run of the node. Pending effects are run in
+    /// the order of their height.
+    pub height: usize,
+    /// Where the node was created, for debugging.
+    pub location: Option<&'static Location<'static>>,
 }
 
+/// Update state of a reactive node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum NodeState {
+    /// The node is up to date.
     Clean,
+    /// One of the sources of the node may have changed, so the sources have
+    /// to be updated before deciding whether to re-run the node.
     Check,
+    /// The node has to be re-run.
     Dirty,
+    /// The node has to be re-run, and its subscribers have already been
+    /// marked.
     DirtyMarked,
 }
 
 #[derive(Clone)]
 pub enum NodeKind {
-    Signal,
-    Effect { computation: AnyComputation },
+    Scope,
+    Signal {
+        equality: Option<AnyEquality>,
+    },
+    Trigger,
+    Effect {
+        computation: AnyComputation,
+        phase: EffectPhase,
+    },
+    Memo {
+        computation: AnyComputation,
+    },
 }
 
-pub type AnyValue = Rc<RefCell<dyn Any>>;
+/// Bound for the values stored inside of reactive nodes.
+///
+/// With the `sync` feature, the values have to be `Send + Sync`, so that they
+/// can be shared with other threads.
+#[cfg(not(feature = "sync"))]
+pub trait Value: 'static {}
+
+#[cfg(not(feature = "sync"))]
+impl<T: 'static> Value for T {}
+
+/// Bound for the values stored inside of reactive nodes.
+///
+/// With the `sync` feature, the values have to be `Send + Sync`, so that they
+/// can be shared with other threads.
+#[cfg(feature = "sync")]
+pub trait Value: Send + Sync + 'static {}
+
+#[cfg(feature = "sync")]
+impl<T: Send + Sync + 'static> Value for T {}
+
+/// Shared pointer used for node values: an `Rc`, or an `Arc` with the `sync`
+/// feature.
+#[cfg(not(feature = "sync"))]
+pub type Shared<T> = Rc<T>;
+
+/// Shared pointer used for node values: an `Rc`, or an `Arc` with the `sync`
+/// feature.
+#[cfg(feature = "sync")]
+pub type Shared<T> = std::sync::Arc<T>;
 
-pub fn wrap_value<T: 'static>(value: T) -> AnyValue {
-    Rc::new(RefCell::new(value))
+#[cfg(not(feature = "sync"))]
+pub type AnyValue = Shared<ValueCell<dyn Any>>;
+
+#[cfg(feature = "sync")]
+pub type AnyValue = Shared<ValueCell<dyn Any + Send + Sync>>;
+
+pub fn wrap_value<T: Value>(value: T) -> AnyValue {
+    Shared::new(ValueCell::new(value))
+}
+
+/// Cell holding the value of a node: a `RefCell`, or a `RwLock` with the
+/// `sync` feature.
+///
+/// In both cases, borrowing a value which is already mutably borrowed fails
+/// instead of blocking.
+pub struct ValueCell<T: ?Sized> {
+    #[cfg(not(feature = "sync"))]
+    inner: RefCell<T>,
+
+    #[cfg(feature = "sync")]
+    inner: std::sync::RwLock<T>,
+}
+
+#[cfg(not(feature = "sync"))]
+pub type ValueRef<'a, T> = std::cell::Ref<'a, T>;
+
+#[cfg(not(feature = "sync"))]
+pub type ValueRefMut<'a, T> = std::cell::RefMut<'a, T>;
+
+#[cfg(feature = "sync")]
+pub type ValueRef<'a, T> = std::sync::RwLockReadGuard<'a, T>;
+
+#[cfg(feature = "sync")]
+pub type ValueRefMut<'a, T> = std::sync::RwLockWriteGuard<'a, T>;
+
+impl<T> ValueCell<T> {
+    pub fn new(value: T) -> ValueCell<T> {
+        ValueCell {
+            inner: value.into(),
+        }
+    }
+}
+
+impl<T: ?Sized> ValueCell<T> {
+    /// Borrows the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is already mutably borrowed.
+    pub fn borrow(&self) -> ValueRef<'_, T> {
+        self.try_borrow()
+            .expect("value is already mutably borrowed")
+    }
+
+    /// Mutably borrows the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is already borrowed.
+    pub fn borrow_mut(&self) -> ValueRefMut<'_, T> {
+        self.try_borrow_mut().expect("value is already borrowed")
+    }
+
+    #[cfg(not(feature = "sync"))]
+    pub fn try_borrow(&self) -> Option<ValueRef<'_, T>> {
+        self.inner.try_borrow().ok()
+    }
+
+    #[cfg(not(feature = "sync"))]
+    pub fn try_borrow_mut(&self) -> Option<ValueRefMut<'_, T>> {
+        self.inner.try_borrow_mut().ok()
+    }
+
+    #[cfg(feature = "sync")]
+    pub fn try_borrow(&self) -> Option<ValueRef<'_, T>> {
+        use std::sync::TryLockError;
+
+        // a panic while the value was borrowed doesn't invalidate it, same as
+        // with a `RefCell`
+        match self.inner.try_read() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    pub fn try_borrow_mut(&self) -> Option<ValueRefMut<'_, T>> {
+        use std::sync::TryLockError;
+
+        match self.inner.try_write() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+}
+
+/// Comparator of values, returning `true` if the values are equal.
+pub type Equality<T> = Rc<dyn Fn(&T, &T) -> bool>;
+
+/// Type-erased comparator of signal values, returning `true` if the values are
+/// equal.
+pub type AnyEquality = Rc<dyn Fn(&dyn Any, &dyn Any) -> bool>;
+
+pub fn wrap_equality<T: Value>(equality: Equality<T>) -> AnyEquality {
+    Rc::new(
+        move |a, b| match (a.downcast_ref::<T>(), b.downcast_ref::<T>()) {
+            (Some(a), Some(b)) => equality(a, b),
+            _ => false,
+        },
+    )
 }
 
 pub trait Computation {
@@ -41,6 +245,9 @@ pub trait Computation {
 
 pub type AnyComputation = Rc<RefCell<dyn Computation>>;
 
+/// Callback run before a node is re-run or disposed.
+pub type Cleanup = Box<dyn FnOnce()>;
+
 struct EffectComputation<T, F> {
     func: F,
     marker: PhantomData<fn(T) -> T>,
@@ -48,7 +255,7 @@ struct EffectComputation<T, F> {
 
 impl<T, F> Computation for EffectComputation<T, F>
 where
-    T: 'static,
+    T: Value,
     F: 'static + Fn(Option<T>) -> T,
 {
     fn run(&self, value: AnyValue) -> bool {
@@ -68,7 +275,7 @@ where
 
 pub fn wrap_effect_computation<T, F>(func: F) -> AnyComputation
 where
-    T: 'static,
+    T: Value,
     F: 'static + Fn(Option<T>) -> T,
 {
     Rc::new(RefCell::new(EffectComputation {
@@ -76,3 +283,42 @@ where
         marker: PhantomData,
     }))
 }
+
+struct MemoComputation<T, F> {
+    func: F,
+    /// Comparator deciding whether the value has changed. `None` means that
+    /// the value is considered changed on every run.
+    equality: Option<Equality<T>>,
+}
+
+impl<T, F> Computation for MemoComputation<T, F>
+where
+    T: Value,
+    F: 'static + Fn(Option<&T>) -> T,
+{
+    fn run(&self, value: AnyValue) -> bool {
+        let old_value = value
+            .borrow_mut()
+            .downcast_mut::<Option<T>>()
+            .unwrap()
+            .take();
+
+        let new_value = (self.func)(old_value.as_ref());
+        let changed = match (&self.equality, &old_value) {
+            (Some(equality), Some(old_value)) => !equality(old_value, &new_value),
+            _ => true,
+        };
+
+        *value.borrow_mut().downcast_mut::<Option<T>>().unwrap() = Some(new_value);
+
+        changed
+    }
+}
+
+pub fn wrap_memo_computation<T, F>(func: F, equality: Option<Equality<T>>) -> AnyComputation
+where
+    T: Value,
+    F: 'static + Fn(Option<&T>) -> T,
+{
+    Rc::new(RefCell::new(MemoComputation { func, equality }))
+}
@@ -0,0 +1,221 @@
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use crate::effect::create_effect;
+use crate::executor::spawn_local;
+use crate::node::Value;
+use crate::runtime::{batch, untrack, WeakRuntime};
+use crate::scope::on_cleanup;
+use crate::signal::{create_signal, Signal};
+
+/// Error of a failed resource future: an `Rc<dyn Error>`, or an
+/// `Arc<dyn Error + Send + Sync>` with the `sync` feature.
+#[cfg(not(feature = "sync"))]
+pub type ResourceError = Rc<dyn Error>;
+
+/// Error of a failed resource future: an `Rc<dyn Error>`, or an
+/// `Arc<dyn Error + Send + Sync>` with the `sync` feature.
+#[cfg(feature = "sync")]
+pub type ResourceError = std::sync::Arc<dyn Error + Send + Sync>;
+
+#[cfg(not(feature = "sync"))]
+type BoxError = Box<dyn Error>;
+
+#[cfg(feature = "sync")]
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Creates a resource, which runs the async `fetcher` with the value of
+/// `source` every time the latter changes.
+///
+/// Futures are spawned on the current executor, see
+/// [`set_executor`](crate::set_executor). When the source changes while the
+/// previous future is still in flight, or the resource's scope is disposed,
+/// the stale future is cancelled. The result is written in the runtime the
+/// resource was created in, regardless of where the executor polls the
+/// future.
+#[track_caller]
+pub fn create_resource<S, T, E, Fut>(
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fut + 'static,
+) -> Resource<S, T>
+where
+    S: 'static,
+    T: Value,
+    E: Into<BoxError>,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    Resource::new(source, fetcher)
+}
+
+/// Reactive value loaded asynchronously.
+pub struct Resource<S, T> {
+    value: Signal<Option<T>>,
+    loading: Signal<bool>,
+    error: Signal<Option<ResourceError>>,
+    marker: PhantomData<S>,
+}
+
+impl<S: 'static, T: Value> Resource<S, T> {
+    #[track_caller]
+    pub fn new<E, Fut>(
+        source: impl Fn() -> S + 'static,
+        fetcher: impl Fn(S) -> Fut + 'static,
+    ) -> Resource<S, T>
+    where
+        E: Into<BoxError>,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let value = create_signal(None);
+        let loading = create_signal(false);
+        let error = create_signal(None);
+
+        // the effect is owned by the runtime, so it must not keep it alive
+        let runtime = WeakRuntime::current();
+
+        create_effect(move |_| {
+            let source = source();
+
+            untrack(|| {
+                let abort = Rc::new(AbortHandle::default());
+                let future = Abortable {
+                    future: Box::pin(fetcher(source)),
+                    abort: abort.clone(),
+                };
+
+                on_cleanup(move || abort.abort());
+
+                loading.set(true);
+
+                let runtime = runtime.clone();
+                spawn_local(async move {
+                    let Some(result) = future.await else {
+                        return;
+                    };
+                    let Some(runtime) = runtime.upgrade() else {
+                        return;
+                    };
+
+                    runtime.enter(|| {
+                        batch(|| {
+                            match result {
+                                Ok(new_value) => {
+                                    value.set(Some(new_value));
+                                    error.set(None);
+                                }
+                                Err(new_error) => {
+                                    error.set(Some(ResourceError::from(new_error.into())));
+                                }
+                            }
+
+                            loading.set(false);
+                        })
+                    });
+                });
+            });
+        });
+
+        Resource {
+            value,
+            loading,
+            error,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the last successfully loaded value, or `None` if nothing has
+    /// been loaded yet.
+    pub fn get(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.value.get()
+    }
+
+    pub fn get_untracked(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.value.get_untracked()
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(Option<&T>) -> Ret) -> Ret {
+        self.value.with(|v| func(v.as_ref()))
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(Option<&T>) -> Ret) -> Ret {
+        self.value.with_untracked(|v| func(v.as_ref()))
+    }
+
+    /// Returns `true` if a future is currently in flight.
+    pub fn loading(&self) -> bool {
+        self.loading.get()
+    }
+
+    /// Returns the error of the last completed future, or `None` if it
+    /// succeeded.
+    pub fn error(&self) -> Option<ResourceError> {
+        self.error.get()
+    }
+}
+
+impl<S, T> fmt::Debug for Resource<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Resource({}, {})",
+            std::any::type_name::<S>(),
+            std::any::type_name::<T>()
+        )
+    }
+}
+
+impl<S, T> Clone for Resource<S, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, T> Copy for Resource<S, T> {}
+
+#[derive(Default)]
+struct AbortHandle {
+    aborted: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl AbortHandle {
+    fn abort(&self) {
+        self.aborted.set(true);
+
+        // wake the task, so that the executor drops it
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Future which resolves to `None` as soon as it's aborted.
+struct Abortable<F> {
+    future: Pin<Box<F>>,
+    abort: Rc<AbortHandle>,
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.abort.aborted.get() {
+            return Poll::Ready(None);
+        }
+
+        *self.abort.waker.borrow_mut() = Some(cx.waker().clone());
+
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
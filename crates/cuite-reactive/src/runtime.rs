@@ -1,20 +1,312 @@
+use std::any::TypeId;
 use std::cell::{Cell, RefCell};
-use std::collections::hash_set;
+use std::cmp::Reverse;
+use std::collections::{hash_set, BinaryHeap};
+use std::fmt;
+use std::panic::Location;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+
+use ahash::{AHashMap, AHashSet};
+use slotmap::{Key, SecondaryMap, SlotMap};
+
+use crate::effect::EffectPhase;
+use crate::error::ReactiveError;
+use crate::executor::{Executor, LocalFuture};
+use crate::inspect::{self, NodeInfo, NodeType, RuntimeStats};
+use crate::node::{
+    AnyComputation, AnyEquality, AnyValue, Cleanup, Node, NodeId, NodeKey, NodeKind, NodeState,
+};
+
+/// Default value for the limit set with [`set_max_effect_iterations`].
+pub const DEFAULT_MAX_EFFECT_ITERATIONS: usize = 100;
+
+/// Sets the maximum number of times a single effect can be run in response to
+/// one update before it's considered to be an infinite loop.
+pub fn set_max_effect_iterations(max_iterations: usize) {
+    with_runtime(|runtime| runtime.set_max_effect_iterations(max_iterations));
+}
+
+/// Runs the given closure, deferring all effects until the outermost batch
+/// ends.
+pub fn batch<Ret>(func: impl FnOnce() -> Ret) -> Ret {
+    with_runtime(|runtime| runtime.batch(func))
+}
+
+/// Runs the given closure without tracking any of the nodes read inside of it.
+pub fn untrack<Ret>(func: impl FnOnce() -> Ret) -> Ret {
+    with_runtime(|runtime| runtime.untrack(func))
+}
+
+thread_local! {
+    /// Runtime used when no other runtime is entered on this thread.
+    static DEFAULT_RUNTIME: Runtime = Runtime::new();
+
+    /// Runtime entered with `Runtime::enter`.
+    static CURRENT_RUNTIME: RefCell<Option<Runtime>> = const { RefCell::new(None) };
+}
+
+/// Runs the given closure with the current runtime: the innermost entered one,
+/// or the default runtime of the thread.
+pub fn with_runtime<Ret>(func: impl FnOnce(&RuntimeInner) -> Ret) -> Ret {
+    let runtime = Runtime::current();
+    func(&runtime.inner)
+}
+
+/// Update sent to a runtime from another thread, which is applied once the
+/// runtime calls [`Runtime::drain_external_updates`].
+pub(crate) type ExternalUpdate = Box<dyn FnOnce() + Send>;
+
+/// Effects scheduled to be run, queued by phase and ordered by height.
+#[derive(Default)]
+struct PendingEffects {
+    queues: [BinaryHeap<Reverse<PendingEffect>>; EffectPhase::COUNT],
+
+    /// Number of effects scheduled so far, used for keeping the effects of the
+    /// same height in the order of scheduling.
+    scheduled: u64,
+
+    /// Effects which are currently in one of the queues, so that an effect is
+    /// queued at most once.
+    queued: AHashSet<NodeId>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct PendingEffect {
+    height: usize,
+    order: u64,
+    id: NodeId,
+}
+
+impl PendingEffects {
+    fn push(&mut self, phase: EffectPhase, height: usize, id: NodeId) {
+        if !self.queued.insert(id) {
+            return;
+        }
+
+        let order = self.scheduled;
+        self.scheduled += 1;
+        self.queues[phase as usize].push(Reverse(PendingEffect { height, order, id }));
+    }
 
-use ahash::AHashSet;
-use slotmap::{SecondaryMap, SlotMap};
+    /// Removes the lowest effect of the earliest phase.
+    fn pop(&mut self) -> Option<NodeId> {
+        let mut queues = self.queues.iter_mut();
+        let id = queues.find_map(|queue| queue.pop().map(|Reverse(effect)| effect.id))?;
+        self.queued.remove(&id);
+        Some(id)
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(BinaryHeap::len).sum()
+    }
+
+    fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        let effects = self.queues.iter().flat_map(BinaryHeap::iter);
+        effects.map(|Reverse(effect)| effect.id)
+    }
+
+    fn clear(&mut self) {
+        self.queues.iter_mut().for_each(BinaryHeap::clear);
+        self.queued.clear();
+    }
+}
+
+/// Callback receiving the nodes leaked by a disposed root.
+type LeakHandler = Rc<dyn Fn(&[NodeInfo])>;
 
-use crate::node::{AnyComputation, AnyValue, Node, NodeId, NodeKind, NodeState};
+/// Callback called after every write of a signal, see `history::Recorder`.
+#[cfg(feature = "serde")]
+pub(crate) type WriteHook = Rc<dyn Fn(NodeId)>;
 
-pub fn with_runtime<Ret>(func: impl FnOnce(&Runtime) -> Ret) -> Ret {
-    thread_local! {
-        static RUNTIME: Runtime = Runtime::default();
+/// Unique identifier of a runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuntimeId(u64);
+
+impl RuntimeId {
+    fn next() -> RuntimeId {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        RuntimeId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
     }
+}
 
-    RUNTIME.with(func)
+impl fmt::Display for RuntimeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-/// Threaded local reactive runtime.
+/// Reactive runtime, which owns a set of nodes (signals, effects, scopes).
+///
+/// Every thread has a default runtime, which is used unless a different one is
+/// entered with [`Runtime::enter`]. Runtimes are independent from each other:
+/// using a node inside of a runtime other than the one it was created in is
+/// reported as [`ReactiveError::RuntimeMismatch`](crate::ReactiveError).
+///
+/// The handle is cheap to clone. The nodes are dropped together with the last
+/// handle.
+#[derive(Clone)]
+pub struct Runtime {
+    inner: Rc<RuntimeInner>,
+}
+
+impl Runtime {
+    pub fn new() -> Runtime {
+        Runtime {
+            inner: Rc::new(RuntimeInner::new(RuntimeId::next())),
+        }
+    }
+
+    /// Returns the current runtime: the innermost entered one, or the default
+    /// runtime of the thread.
+    pub fn current() -> Runtime {
+        let current = CURRENT_RUNTIME.with_borrow(|v| v.clone());
+        current.unwrap_or_else(|| DEFAULT_RUNTIME.with(Runtime::clone))
+    }
+
+    pub fn id(&self) -> RuntimeId {
+        self.inner.id
+    }
+
+    /// Runs the given closure with this runtime as the current one, so that
+    /// all nodes created inside of it are owned by this runtime.
+    pub fn enter<Ret>(&self, func: impl FnOnce() -> Ret) -> Ret {
+        let prev = CURRENT_RUNTIME.replace(Some(self.clone()));
+
+        // restore the previous runtime even if the closure panics
+        struct Restore(Option<Runtime>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_RUNTIME.set(self.0.take());
+            }
+        }
+
+        let _restore = Restore(prev);
+        func()
+    }
+
+    /// Returns a snapshot of all nodes owned by the runtime, with their
+    /// relationships, for debugging.
+    pub fn nodes(&self) -> Vec<NodeInfo> {
+        let ids = self.inner.node_ids();
+        ids.into_iter()
+            .filter_map(|id| self.inner.node_info(id))
+            .collect()
+    }
+
+    /// Returns a snapshot of a single node, or `None` if the node doesn't
+    /// exist in this runtime.
+    pub fn node(&self, id: NodeId) -> Option<NodeInfo> {
+        self.inner.node_info(id)
+    }
+
+    /// Exports the graph of the nodes in the Graphviz DOT format.
+    ///
+    /// Solid edges go from sources to their subscribers, dashed edges from
+    /// owners to the nodes they own.
+    pub fn to_dot(&self) -> String {
+        inspect::to_dot(&self.nodes())
+    }
+
+    /// Returns the number of live nodes and of the dependencies between them.
+    pub fn stats(&self) -> RuntimeStats {
+        self.inner.stats()
+    }
+
+    /// Makes writes only schedule the effects, which are then run when the
+    /// host calls [`Runtime::flush`], e.g. once per frame. Effects are still
+    /// run right away when they are created.
+    ///
+    /// Switching back to immediate mode runs the pending effects.
+    pub fn set_deferred(&self, deferred: bool) {
+        self.enter(|| self.inner.set_deferred(deferred));
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.inner.is_deferred()
+    }
+
+    /// Runs the pending effects, including the ones scheduled while
+    /// flushing.
+    pub fn flush(&self) {
+        self.enter(|| self.inner.flush());
+    }
+
+    /// Returns whether there are effects waiting to be run by
+    /// [`Runtime::flush`], i.e. whether the host should schedule a new
+    /// frame.
+    pub fn is_dirty(&self) -> bool {
+        self.inner.is_dirty()
+    }
+
+    /// Sets the callback receiving the leaked nodes, which is called when a
+    /// root is disposed. Without a handler, leaks aren't checked.
+    ///
+    /// Leaks are only detected in debug builds: a leak is a live node which
+    /// isn't owned by a root created with [`create_root`](crate::create_root),
+    /// directly or through its ancestors, e.g. a signal created outside of any
+    /// root. Nothing disposes such nodes, except for dropping the runtime.
+    /// Each leaked node is reported once.
+    pub fn set_leak_handler(&self, handler: impl Fn(&[NodeInfo]) + 'static) {
+        self.inner.set_leak_handler(Rc::new(handler));
+    }
+
+    /// Applies the updates sent to this runtime from other threads (see
+    /// [`SignalSender`](crate::SignalSender)), in a single batch. Returns the
+    /// number of applied updates.
+    ///
+    /// The host is expected to call it regularly, e.g. once per frame.
+    pub fn drain_external_updates(&self) -> usize {
+        self.enter(|| {
+            self.inner.batch(|| {
+                let mut count = 0;
+                while let Ok(update) = self.inner.external_receiver.try_recv() {
+                    update();
+                    count += 1;
+                }
+                count
+            })
+        })
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Runtime::new()
+    }
+}
+
+/// Handle of a runtime which doesn't keep it alive, e.g. for code stored
+/// inside of the runtime's own nodes.
+#[derive(Clone)]
+pub(crate) struct WeakRuntime {
+    inner: Weak<RuntimeInner>,
+}
+
+impl WeakRuntime {
+    /// Returns the current runtime, see [`Runtime::current`].
+    pub fn current() -> WeakRuntime {
+        WeakRuntime {
+            inner: Rc::downgrade(&Runtime::current().inner),
+        }
+    }
+
+    /// Returns the runtime, or `None` if it has been dropped.
+    pub fn upgrade(&self) -> Option<Runtime> {
+        let inner = self.inner.upgrade()?;
+        Some(Runtime { inner })
+    }
+}
+
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Runtime({})", self.inner.id)
+    }
+}
+
+/// State of a reactive runtime.
 ///
 /// Manages the reactive nodes (signals, effects, scopes) and their lifetime.
 ///
@@ -24,30 +316,42 @@ pub fn with_runtime<Ret>(func: impl FnOnce(&Runtime) -> Ret) -> Ret {
 ///
 ///  - Parent - [Child]. This is a forest which determines the lifetime of
 ///    nodes, their ownership, and handles cleanup.
-#[derive(Default)]
-pub struct Runtime {
+pub struct RuntimeInner {
+    id: RuntimeId,
+
     /// Reactive nodes: signals, effects, scopes, etc
-    nodes: RefCell<SlotMap<NodeId, Node>>,
+    nodes: RefCell<SlotMap<NodeKey, Node>>,
 
     /// Mapping between nodes and their subscribers.
     ///
     /// If node A is a subscriber of node B, then updates of B will also cause
     /// an update of A.
-    node_subscribers: RefCell<SecondaryMap<NodeId, RefCell<AHashSet<NodeId>>>>,
+    node_subscribers: RefCell<SecondaryMap<NodeKey, RefCell<AHashSet<NodeId>>>>,
 
     /// Mapping between nodes and their sources, i.e. dependencies.
     ///
     /// If A is a subscriber of B, then B is a source of A, and vice versa.
-    node_sources: RefCell<SecondaryMap<NodeId, RefCell<AHashSet<NodeId>>>>,
+    ///
+    /// The sources are kept in the order they were first read during the
+    /// last run of the node.
+    node_sources: RefCell<SecondaryMap<NodeKey, RefCell<Vec<NodeId>>>>,
 
     /// Mapping between nodes and their parent. When parent is disposed, all of
     /// the descendants in the hierarchy are also disposed.
-    node_parents: RefCell<SecondaryMap<NodeId, NodeId>>,
+    node_parents: RefCell<SecondaryMap<NodeKey, NodeId>>,
 
     /// Mapping between nodes and their children.
     ///
     /// Reversed `node_parents` mapping.
-    node_children: RefCell<SecondaryMap<NodeId, RefCell<AHashSet<NodeId>>>>,
+    node_children: RefCell<SecondaryMap<NodeKey, RefCell<AHashSet<NodeId>>>>,
+
+    /// Mapping between nodes and their cleanup callbacks, in the order of
+    /// registration.
+    node_cleanups: RefCell<SecondaryMap<NodeKey, Vec<Cleanup>>>,
+
+    /// Mapping between nodes and the context values they provide to their
+    /// descendants, keyed by the type of the value.
+    node_contexts: RefCell<SecondaryMap<NodeKey, AHashMap<TypeId, AnyValue>>>,
 
     /// Current scope which will be implicitly assigned as a parent for all
     /// nodes created under it.
@@ -61,20 +365,116 @@ pub struct Runtime {
     /// node.
     observer: Cell<Option<NodeId>>,
 
-    /// List of effects scheduled to be run during `run_effects`
-    pending_effects: RefCell<Vec<NodeId>>,
+    /// Queues of effects scheduled to be run during `run_effects`
+    pending_effects: RefCell<PendingEffects>,
+
+    /// Whether `run_effects` is currently running. Effects scheduled during
+    /// that time are appended to the queue and run after the current effect
+    /// completes.
+    running_effects: Cell<bool>,
+
+    /// Maximum number of times a single effect can be run during one
+    /// `run_effects` call before it's considered to be an infinite loop.
+    ///
+    /// `None` means `DEFAULT_MAX_EFFECT_ITERATIONS`.
+    max_effect_iterations: Cell<Option<usize>>,
+
+    /// Number of currently active (nested) batches. While it's non-zero,
+    /// `run_effects` does nothing.
+    batch_depth: Cell<usize>,
+
+    /// Whether the effects are deferred until `flush` is called, instead of
+    /// being run by `run_effects`.
+    deferred: Cell<bool>,
+
+    /// Executor used for spawning futures, e.g. by resources.
+    executor: RefCell<Option<Rc<dyn Executor>>>,
+
+    /// Channel of the updates sent from other threads, see
+    /// `Runtime::drain_external_updates`.
+    external_sender: mpsc::Sender<ExternalUpdate>,
+    external_receiver: mpsc::Receiver<ExternalUpdate>,
+
+    /// Live roots created with `create_root`. Nodes which aren't owned by
+    /// any of them are leaks.
+    #[cfg(debug_assertions)]
+    roots: RefCell<AHashSet<NodeId>>,
+
+    /// Live leaked nodes which have already been passed to the leak handler.
+    #[cfg(debug_assertions)]
+    reported_leaks: RefCell<AHashSet<NodeId>>,
+
+    /// Callback receiving the leaked nodes, see `Runtime::set_leak_handler`.
+    leak_handler: RefCell<Option<LeakHandler>>,
+
+    /// Callback called after every write of a signal.
+    #[cfg(feature = "serde")]
+    write_hook: RefCell<Option<WriteHook>>,
 }
 
-impl Runtime {
+impl RuntimeInner {
+    fn new(id: RuntimeId) -> RuntimeInner {
+        let (external_sender, external_receiver) = mpsc::channel();
+
+        RuntimeInner {
+            id,
+            nodes: Default::default(),
+            node_subscribers: Default::default(),
+            node_sources: Default::default(),
+            node_parents: Default::default(),
+            node_children: Default::default(),
+            node_cleanups: Default::default(),
+            node_contexts: Default::default(),
+            scope: Default::default(),
+            observer: Default::default(),
+            pending_effects: Default::default(),
+            running_effects: Default::default(),
+            max_effect_iterations: Default::default(),
+            batch_depth: Default::default(),
+            deferred: Default::default(),
+            executor: Default::default(),
+            external_sender,
+            external_receiver,
+            #[cfg(debug_assertions)]
+            roots: Default::default(),
+            #[cfg(debug_assertions)]
+            reported_leaks: Default::default(),
+            leak_handler: Default::default(),
+            #[cfg(feature = "serde")]
+            write_hook: Default::default(),
+        }
+    }
+
+    /// Returns the key of a node inside of this runtime. Nodes owned by other
+    /// runtimes get a null key, which doesn't match any node.
+    fn key(&self, id: NodeId) -> NodeKey {
+        match id.runtime() == self.id {
+            true => id.key(),
+            false => NodeKey::null(),
+        }
+    }
+
+    /// Returns a sender of updates which are applied by
+    /// `Runtime::drain_external_updates`.
+    pub fn external_sender(&self) -> mpsc::Sender<ExternalUpdate> {
+        self.external_sender.clone()
+    }
+
+    /// Returns whether the node is owned by this runtime.
+    pub fn owns(&self, id: NodeId) -> bool {
+        id.runtime() == self.id
+    }
+
     /// Creates a node and assigns it to the current scope.
     pub fn create_node(&self, node: Node) -> NodeId {
-        let id = self.nodes.borrow_mut().insert(node);
+        let key = self.nodes.borrow_mut().insert(node);
+        let id = NodeId::new(self.id, key);
 
         if let Some(scope) = self.scope.get() {
-            self.node_parents.borrow_mut().insert(id, scope);
+            self.node_parents.borrow_mut().insert(self.key(id), scope);
 
             let node_children = &mut self.node_children.borrow_mut();
-            let children = node_children.entry(id).map(|v| v.or_default());
+            let children = node_children.entry(self.key(scope)).map(|v| v.or_default());
             if let Some(children) = children {
                 children.borrow_mut().insert(id);
             }
@@ -83,45 +483,184 @@ impl Runtime {
         id
     }
 
+    /// Creates a scope. Scopes don't have a value and only serve as owners of
+    /// other nodes.
+    pub fn create_scope(&self) -> NodeId {
+        self.create_node(Node {
+            value: None,
+            state: NodeState::Clean,
+            kind: NodeKind::Scope,
+            height: 0,
+            location: None,
+        })
+    }
+
     /// Creates a signal with a specified initial value.
     pub fn create_signal(&self, value: AnyValue) -> NodeId {
+        self.create_signal_with_equality(value, None)
+    }
+
+    /// Creates a signal with a specified initial value and a comparator used
+    /// by setters to skip notifying the subscribers when the value is
+    /// unchanged.
+    pub fn create_signal_with_equality(
+        &self,
+        value: AnyValue,
+        equality: Option<AnyEquality>,
+    ) -> NodeId {
         self.create_node(Node {
             value: Some(value),
             state: NodeState::Clean,
-            kind: NodeKind::Signal,
+            kind: NodeKind::Signal { equality },
+            height: 0,
+            location: None,
         })
     }
 
-    /// Creates an effect with a specified initial value and a computation.
+    /// Creates a trigger, i.e. a signal without a value.
+    pub fn create_trigger(&self) -> NodeId {
+        self.create_node(Node {
+            value: None,
+            state: NodeState::Clean,
+            kind: NodeKind::Trigger,
+            height: 0,
+            location: None,
+        })
+    }
+
+    /// Creates an effect with a specified initial value, a computation, and
+    /// the phase in which it's run when scheduled.
     ///
     /// Note that the effect will not be run unless you call
     /// `update_if_necessary`.
-    pub fn create_effect(&self, value: AnyValue, computation: AnyComputation) -> NodeId {
+    pub fn create_effect(
+        &self,
+        value: AnyValue,
+        computation: AnyComputation,
+        phase: EffectPhase,
+    ) -> NodeId {
         self.create_node(Node {
             value: Some(value),
             state: NodeState::Dirty,
-            kind: NodeKind::Effect { computation },
+            kind: NodeKind::Effect { computation, phase },
+            height: 0,
+            location: None,
         })
     }
 
+    /// Creates a memo with a specified initial value and a computation.
+    ///
+    /// Memos are lazy: the computation is run when the value is first
+    /// requested through `update_if_necessary`.
+    pub fn create_memo(&self, value: AnyValue, computation: AnyComputation) -> NodeId {
+        self.create_node(Node {
+            value: Some(value),
+            state: NodeState::Dirty,
+            kind: NodeKind::Memo { computation },
+            height: 0,
+            location: None,
+        })
+    }
+
+    /// Records where the node was created, see [`Runtime::nodes`].
+    pub fn set_node_location(&self, id: NodeId, location: &'static Location<'static>) {
+        if let Some(node) = self.nodes.borrow_mut().get_mut(self.key(id)) {
+            node.location = Some(location);
+        }
+    }
+
+    /// Returns a snapshot of the node and its relationships.
+    pub fn node_info(&self, id: NodeId) -> Option<NodeInfo> {
+        let key = self.key(id);
+        let nodes = self.nodes.borrow();
+        let node = nodes.get(key)?;
+
+        let sorted = |set: Option<&RefCell<AHashSet<NodeId>>>| {
+            let mut ids = set
+                .map(|v| v.borrow().iter().copied().collect::<Vec<_>>())
+                .unwrap_or_default();
+            ids.sort();
+            ids
+        };
+
+        Some(NodeInfo {
+            id,
+            kind: NodeType::of(&node.kind),
+            state: node.state,
+            height: node.height,
+            location: node.location,
+            parent: self.node_parents.borrow().get(key).copied(),
+            children: sorted(self.node_children.borrow().get(key)),
+            sources: self
+                .node_sources
+                .borrow()
+                .get(key)
+                .map(|v| v.borrow().clone())
+                .unwrap_or_default(),
+            subscribers: sorted(self.node_subscribers.borrow().get(key)),
+        })
+    }
+
+    /// Returns the ids of all nodes, ordered by creation (reused slots aside).
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        let nodes = self.nodes.borrow();
+        nodes.keys().map(|key| NodeId::new(self.id, key)).collect()
+    }
+
     /// Returns the value of a node, if the node exists and has a value.
     pub fn get_node_value(&self, id: NodeId) -> Option<AnyValue> {
         let nodes = self.nodes.borrow();
-        let node = nodes.get(id)?;
+        let node = nodes.get(self.key(id))?;
         node.value.clone()
     }
 
+    /// Runs the given closure with the value of a node mutably borrowed and
+    /// downcast to `S`, e.g. the internal state of a collection.
+    ///
+    /// Errors are reported with the type `T`, i.e. the type of the value as
+    /// seen by the user of the node.
+    pub fn with_node_value_mut<T, S: 'static, Ret>(
+        &self,
+        id: NodeId,
+        func: impl FnOnce(&mut S) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        let value = self
+            .get_node_value(id)
+            .ok_or_else(|| ReactiveError::missing::<T>(self, id))?;
+        let mut borrow = value
+            .try_borrow_mut()
+            .ok_or_else(|| ReactiveError::reentrant_borrow::<T>(id))?;
+        let casted = borrow
+            .downcast_mut::<S>()
+            .ok_or_else(|| ReactiveError::type_mismatch::<T>(id))?;
+        Ok(func(casted))
+    }
+
+    /// Returns the comparator of a signal, if it has one.
+    pub fn node_equality(&self, id: NodeId) -> Option<AnyEquality> {
+        let nodes = self.nodes.borrow();
+        match &nodes.get(self.key(id))?.kind {
+            NodeKind::Signal { equality } => equality.clone(),
+            _ => None,
+        }
+    }
+
     fn node_state(&self, id: NodeId) -> NodeState {
         let nodes = self.nodes.borrow();
-        match nodes.get(id) {
+        match nodes.get(self.key(id)) {
             Some(node) => node.state,
             _ => NodeState::Clean,
         }
     }
 
+    fn node_height(&self, id: NodeId) -> usize {
+        let nodes = self.nodes.borrow();
+        nodes.get(self.key(id)).map_or(0, |node| node.height)
+    }
+
     fn mark_clean(&self, id: NodeId) {
         let mut nodes = self.nodes.borrow_mut();
-        if let Some(node) = nodes.get_mut(id) {
+        if let Some(node) = nodes.get_mut(self.key(id)) {
             node.state = NodeState::Clean;
         }
     }
@@ -129,27 +668,30 @@ impl Runtime {
     /// Marks the node dirty. All of its descendants in the subscriber hierarchy
     /// are marked as check, i.e. they will be updated only if one of their
     /// sources actually changes.
+    ///
+    /// A running node is clean, so an effect writing a signal it has already
+    /// read is scheduled again, and re-run once it completes.
     pub fn mark_descendants_dirty(&self, root_id: NodeId) {
         let mut nodes = self.nodes.borrow_mut();
         let mut pending_effects = self.pending_effects.borrow_mut();
         let subscribers = self.node_subscribers.borrow();
-        let observer = self.observer.get();
 
-        match nodes.get_mut(root_id) {
-            Some(node) => {
-                if node.state == NodeState::DirtyMarked {
-                    return;
-                }
+        let Some(node) = nodes.get_mut(self.key(root_id)) else {
+            return;
+        };
 
-                node.state = NodeState::DirtyMarked
-            }
-            None => return,
+        if node.state == NodeState::DirtyMarked {
+            return;
         }
 
-        let Some(root_children) = subscribers.get(root_id).map(|v| v.borrow()) else {
+        let root_children = subscribers.get(self.key(root_id)).map(|v| v.borrow());
+        let Some(root_children) = root_children.filter(|c| !c.is_empty()) else {
+            // nobody is going to pull the update, so the node stays clean
             return;
         };
 
+        node.state = NodeState::DirtyMarked;
+
         // DFS using a stack of iterators
 
         // define a self-referential struct for storing the iterator alongside the
@@ -180,7 +722,7 @@ impl Runtime {
                 };
 
                 loop {
-                    let Some(node) = nodes.get_mut(child) else {
+                    let Some(node) = nodes.get_mut(self.key(child)) else {
                         // node is disposed
                         return Operation::Continue;
                     };
@@ -195,13 +737,12 @@ impl Runtime {
                         node.state = NodeState::Check;
                     }
 
-                    if let NodeKind::Effect { .. } = &node.kind {
-                        if observer != Some(child) {
-                            pending_effects.push(child)
-                        }
+                    if let NodeKind::Effect { phase, .. } = &node.kind {
+                        pending_effects.push(*phase, node.height, child)
                     }
 
-                    let Some(children) = subscribers.get(child).map(|c| c.borrow()) else {
+                    let Some(children) = subscribers.get(self.key(child)).map(|c| c.borrow())
+                    else {
                         // no children
                         return Operation::Continue;
                     };
@@ -235,14 +776,171 @@ impl Runtime {
     }
 
     /// Runs all the pending effects.
+    ///
+    /// The effects are run phase by phase: an effect is only run once there
+    /// are no pending effects of the earlier phases, including the ones
+    /// scheduled by the effects run so far. Effects of the same phase are run
+    /// in the order of their height, i.e. effects closer to the signals go
+    /// first, so that an effect never runs before an effect it depends on.
+    /// Effects of the same height are run in the order they were scheduled.
+    ///
+    /// Does nothing if called inside a batch: the effects will be run once the
+    /// outermost batch ends. Does nothing if the runtime is deferred as well:
+    /// the effects will be run by `flush`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some effect is run more than `max_effect_iterations` times,
+    /// which usually means that effects are updating each other's sources in
+    /// an infinite loop.
     pub fn run_effects(&self) {
-        let mut effects = self.pending_effects.take();
+        if self.batch_depth.get() > 0 || self.deferred.get() {
+            return;
+        }
+
+        self.flush();
+    }
+
+    /// Runs all the pending effects, even if the runtime is deferred. See
+    /// `run_effects` for the order.
+    ///
+    /// Does nothing if called from inside an effect: newly scheduled effects
+    /// are run by the outer call after the current effect completes.
+    pub fn flush(&self) {
+        if self.running_effects.get() {
+            return;
+        }
+
+        // stop running even if an effect panics, otherwise effects would
+        // never run again
+        struct EndRun<'a>(&'a Cell<bool>);
+
+        impl Drop for EndRun<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+
+        self.running_effects.set(true);
+        let _end_run = EndRun(&self.running_effects);
+
+        let max_iterations = self.max_effect_iterations();
+        let mut iterations = AHashMap::<NodeId, usize>::new();
+
+        loop {
+            let effect_id = self.pending_effects.borrow_mut().pop();
+            let Some(effect_id) = effect_id else {
+                break;
+            };
+
+            // effects whose sources turned out to be unchanged don't count
+            if !self.update_if_necessary(effect_id) {
+                continue;
+            }
+
+            let count = iterations.entry(effect_id).or_default();
+            *count += 1;
+
+            if *count > max_iterations {
+                self.abandon_pending_effects();
+                panic!(
+                    "effect {effect_id:?} has been run more than {max_iterations} times, possible \
+                     infinite loop"
+                );
+            }
+        }
+    }
+
+    /// Drops the pending effects without running them, and resets the states
+    /// of the nodes marked for them, so that later writes schedule the
+    /// effects again.
+    ///
+    /// Memos which may be stale are marked dirty, so that they're re-run when
+    /// read next time.
+    fn abandon_pending_effects(&self) {
+        self.pending_effects.borrow_mut().clear();
+
+        for node in self.nodes.borrow_mut().values_mut() {
+            node.state = match node.kind {
+                NodeKind::Memo { .. } if node.state != NodeState::Clean => NodeState::Dirty,
+                _ => NodeState::Clean,
+            };
+        }
+    }
+
+    /// Returns whether the effects are deferred until `flush` is called.
+    pub fn is_deferred(&self) -> bool {
+        self.deferred.get()
+    }
+
+    /// Sets whether the effects are deferred until `flush` is called. When
+    /// switching back, the pending effects are run right away.
+    pub fn set_deferred(&self, deferred: bool) {
+        self.deferred.set(deferred);
+        self.run_effects();
+    }
+
+    /// Returns whether there are effects waiting to be run.
+    pub fn is_dirty(&self) -> bool {
+        let pending_effects = self.pending_effects.borrow();
+        let mut ids = pending_effects.ids();
+        ids.any(|id| self.node_state(id) != NodeState::Clean)
+    }
+
+    /// Returns the maximum number of times a single effect can be run during
+    /// one `run_effects` call.
+    pub fn max_effect_iterations(&self) -> usize {
+        self.max_effect_iterations
+            .get()
+            .unwrap_or(DEFAULT_MAX_EFFECT_ITERATIONS)
+    }
+
+    /// Sets the maximum number of times a single effect can be run during one
+    /// `run_effects` call.
+    pub fn set_max_effect_iterations(&self, max_iterations: usize) {
+        self.max_effect_iterations.set(Some(max_iterations));
+    }
 
-        for effect_id in effects.drain(..) {
-            self.update_if_necessary(effect_id);
+    /// Runs the given closure as a batch: effects scheduled by the signal
+    /// updates inside it are only run when the outermost batch ends.
+    pub fn batch<Ret>(&self, func: impl FnOnce() -> Ret) -> Ret {
+        // leave the batch even if the closure panics, otherwise effects would
+        // never run again
+        struct EndBatch<'a>(&'a Cell<usize>);
+
+        impl Drop for EndBatch<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() - 1);
+            }
         }
 
-        *self.pending_effects.borrow_mut() = effects;
+        self.batch_depth.set(self.batch_depth.get() + 1);
+        let end_batch = EndBatch(&self.batch_depth);
+        let ret = func();
+        drop(end_batch);
+
+        self.run_effects();
+
+        ret
+    }
+
+    /// Sets the executor used for spawning futures.
+    pub fn set_executor(&self, executor: Rc<dyn Executor>) {
+        *self.executor.borrow_mut() = Some(executor);
+    }
+
+    /// Spawns a future on the current executor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no executor was set with `set_executor`.
+    pub fn spawn_local(&self, future: LocalFuture) {
+        let executor = self.executor.borrow().clone();
+        let Some(executor) = executor else {
+            panic!("no executor is set, see `set_executor`");
+        };
+
+        executor.spawn_local(future);
     }
 
     /// Updates the node only if necessary.
@@ -250,18 +948,28 @@ impl Runtime {
     /// If it's marked as check, the sources will be recursively updated too.
     ///
     /// If it's marked as dirty, it will be updated regardless of the state of
-    /// the sources.
-    pub fn update_if_necessary(&self, node_id: NodeId) {
-        if self.node_state(node_id) == NodeState::Check {
-            let sources = {
-                self.node_sources
-                    .borrow()
-                    .get(node_id)
-                    .map(|v| v.borrow().iter().copied().collect::<Vec<_>>())
-            };
+    /// the sources. The node is marked clean before it's run, so that writes
+    /// to its sources during the run mark it again.
+    ///
+    /// Returns whether the node was run.
+    pub fn update_if_necessary(&self, node_id: NodeId) -> bool {
+        // writes performed while updating the node are batched, so that the
+        // effects they schedule are run only after the node is clean again
+        self.batch(|| {
+            if self.node_state(node_id) == NodeState::Check {
+                let sources = {
+                    self.node_sources
+                        .borrow()
+                        .get(self.key(node_id))
+                        .map(|v| v.borrow().clone())
+                };
 
-            if let Some(sources) = sources {
-                for source in sources {
+                // the sources are checked in the order the node read them, so
+                // that a source read only behind a condition (e.g. an
+                // `unwrap` guarded by `is_some`) isn't updated once the
+                // condition has changed: the node is dirty by then, and reads
+                // only what it still needs while running
+                for source in sources.into_iter().flatten() {
                     self.update_if_necessary(source);
 
                     if self.node_state(node_id) >= NodeState::Dirty {
@@ -269,14 +977,17 @@ impl Runtime {
                     }
                 }
             }
-        }
 
-        if self.node_state(node_id) >= NodeState::Dirty {
+            if self.node_state(node_id) < NodeState::Dirty {
+                self.mark_clean(node_id);
+                return false;
+            }
+
             self.cleanup_children(node_id);
+            self.mark_clean(node_id);
             self.update(node_id);
-        }
-
-        self.mark_clean(node_id);
+            true
+        })
     }
 
     /// Runs the given closure in the context of the provided observer.
@@ -284,31 +995,242 @@ impl Runtime {
     /// For the duration of the closure, `observer` will become the new
     /// `observer` and `scope` as well.
     pub fn with_observer<Ret>(&self, observer: NodeId, func: impl FnOnce() -> Ret) -> Ret {
-        let prev_observer = self.observer.take();
-        let prev_scope = self.scope.take();
+        self.with_context(Some(observer), Some(observer), func)
+    }
 
-        self.observer.set(Some(observer));
-        self.scope.set(Some(observer));
+    /// Runs the given closure with the provided observer and scope. The
+    /// previous ones are restored afterwards, even if the closure panics, so
+    /// that a panicking effect doesn't stay the observer of later reads.
+    fn with_context<Ret>(
+        &self,
+        observer: Option<NodeId>,
+        scope: Option<NodeId>,
+        func: impl FnOnce() -> Ret,
+    ) -> Ret {
+        struct Restore<'a> {
+            runtime: &'a RuntimeInner,
+            observer: Option<NodeId>,
+            scope: Option<NodeId>,
+        }
 
-        let ret = func();
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                self.runtime.observer.set(self.observer);
+                self.runtime.scope.set(self.scope);
+            }
+        }
+
+        let _restore = Restore {
+            runtime: self,
+            observer: self.observer.replace(observer),
+            scope: self.scope.replace(scope),
+        };
 
-        self.observer.set(prev_observer);
-        self.scope.set(prev_scope);
+        func()
+    }
 
-        ret
+    /// Returns the current scope, which is implicitly assigned as a parent for
+    /// all nodes created under it.
+    pub fn current_scope(&self) -> Option<NodeId> {
+        self.scope.get()
+    }
+
+    /// Runs the given closure with the provided scope (or with no scope at
+    /// all), keeping the observer intact.
+    pub fn with_scope<Ret>(&self, scope: Option<NodeId>, func: impl FnOnce() -> Ret) -> Ret {
+        self.with_context(self.observer.get(), scope, func)
+    }
+
+    /// Runs the given closure without an observer, so that nothing is tracked
+    /// inside of it.
+    ///
+    /// Unlike `with_observer`, `scope` is kept intact, so nodes created inside
+    /// the closure are still owned by the current scope.
+    pub fn untrack<Ret>(&self, func: impl FnOnce() -> Ret) -> Ret {
+        self.with_context(None, self.scope.get(), func)
+    }
+
+    /// Registers a callback which will be run before the current scope is
+    /// re-run or disposed.
+    ///
+    /// If there's no current scope, the callback will never be run.
+    pub fn on_cleanup(&self, func: Cleanup) {
+        let Some(scope) = self.scope.get() else {
+            return;
+        };
+
+        let mut node_cleanups = self.node_cleanups.borrow_mut();
+        if let Some(cleanups) = node_cleanups.entry(self.key(scope)) {
+            cleanups.or_default().push(func);
+        }
+    }
+
+    /// Provides a context value to the current scope and all of its
+    /// descendants, replacing the previous value of the same type.
+    ///
+    /// If there's no current scope, the value is discarded.
+    pub fn provide_context(&self, type_id: TypeId, value: AnyValue) {
+        let Some(scope) = self.scope.get() else {
+            return;
+        };
+
+        let mut node_contexts = self.node_contexts.borrow_mut();
+        if let Some(contexts) = node_contexts.entry(self.key(scope)) {
+            contexts.or_default().insert(type_id, value);
+        }
+    }
+
+    /// Finds the context value of the given type, provided by the nearest
+    /// ancestor of the current scope (including the scope itself).
+    pub fn get_context(&self, type_id: TypeId) -> Option<AnyValue> {
+        let node_contexts = self.node_contexts.borrow();
+        let node_parents = self.node_parents.borrow();
+
+        let mut current = self.scope.get();
+        while let Some(node_id) = current {
+            let value = node_contexts
+                .get(self.key(node_id))
+                .and_then(|v| v.get(&type_id));
+            if let Some(value) = value {
+                return Some(value.clone());
+            }
+
+            current = node_parents.get(self.key(node_id)).copied();
+        }
+
+        None
+    }
+
+    /// Creates a root scope without a parent and runs the given closure inside
+    /// of it.
+    ///
+    /// For the duration of the closure, there's no observer, and the new root
+    /// becomes the `scope`.
+    pub fn create_root<Ret>(&self, func: impl FnOnce(NodeId) -> Ret) -> Ret {
+        let root = self.with_scope(None, || self.create_scope());
+
+        #[cfg(debug_assertions)]
+        self.roots.borrow_mut().insert(root);
+
+        self.with_context(None, Some(root), || func(root))
+    }
+
+    /// Disposes a root created with `create_root`.
+    ///
+    /// In debug builds, the leaked nodes which haven't been reported yet are
+    /// passed to the leak handler, if any.
+    pub fn dispose_root(&self, root: NodeId) {
+        self.dispose_node(root);
+
+        #[cfg(debug_assertions)]
+        {
+            let handler = self.leak_handler.borrow().clone();
+            let Some(handler) = handler else {
+                return;
+            };
+
+            let mut reported = self.reported_leaks.borrow_mut();
+            let mut leaked = self.leaked_nodes();
+            leaked.retain(|&id| reported.insert(id));
+            drop(reported);
+
+            let leaked = leaked
+                .into_iter()
+                .filter_map(|id| self.node_info(id))
+                .collect::<Vec<_>>();
+
+            if !leaked.is_empty() {
+                handler(&leaked);
+            }
+        }
+    }
+
+    /// Returns the live nodes which aren't owned by a live root, directly or
+    /// through their ancestors.
+    #[cfg(debug_assertions)]
+    fn leaked_nodes(&self) -> Vec<NodeId> {
+        let nodes = self.nodes.borrow();
+        let parents = self.node_parents.borrow();
+        let roots = self.roots.borrow();
+
+        let ids = nodes.keys().map(|key| NodeId::new(self.id, key));
+        ids.filter(|&id| {
+            let mut owner = id;
+            while let Some(&parent) = parents.get(self.key(owner)) {
+                owner = parent;
+            }
+            !roots.contains(&owner)
+        })
+        .collect()
+    }
+
+    /// Sets the callback receiving the nodes leaked by a disposed root.
+    pub fn set_leak_handler(&self, handler: LeakHandler) {
+        *self.leak_handler.borrow_mut() = Some(handler);
+    }
+
+    /// Sets the callback called after every write of a signal, replacing the
+    /// previous one.
+    #[cfg(feature = "serde")]
+    pub fn set_write_hook(&self, hook: Option<WriteHook>) {
+        *self.write_hook.borrow_mut() = hook;
+    }
+
+    /// Calls the write hook, if any, after the signal was written.
+    #[cfg(feature = "serde")]
+    pub fn notify_write(&self, id: NodeId) {
+        // the hook may write signals itself
+        let hook = self.write_hook.borrow().clone();
+        if let Some(hook) = hook {
+            hook(id);
+        }
+    }
+
+    /// Returns the number of live nodes by kind, and of the edges between them.
+    pub fn stats(&self) -> RuntimeStats {
+        let mut stats = RuntimeStats::default();
+
+        for node in self.nodes.borrow().values() {
+            let count = match node.kind {
+                NodeKind::Scope => &mut stats.scopes,
+                NodeKind::Signal { .. } => &mut stats.signals,
+                NodeKind::Trigger => &mut stats.triggers,
+                NodeKind::Effect { .. } => &mut stats.effects,
+                NodeKind::Memo { .. } => &mut stats.memos,
+            };
+            *count += 1;
+        }
+
+        let subscribers = self.node_subscribers.borrow();
+        stats.edges = subscribers.values().map(|v| v.borrow().len()).sum();
+        stats.pending_effects = self.pending_effects.borrow().len();
+
+        stats
     }
 
     fn update(&self, node_id: NodeId) {
-        let Some(node) = self.nodes.borrow().get(node_id).cloned() else {
+        let Some(node) = self.nodes.borrow().get(self.key(node_id)).cloned() else {
             return;
         };
 
         let changed = match node.kind {
-            NodeKind::Signal => true,
-            NodeKind::Effect { computation } => {
+            NodeKind::Scope | NodeKind::Signal { .. } | NodeKind::Trigger => true,
+            NodeKind::Effect { computation, .. } | NodeKind::Memo { computation } => {
                 let Some(value) = node.value else { return };
 
-                self.with_observer(node_id, || computation.borrow().run(value))
+                // sources are tracked from scratch on every run, so that the
+                // ones which are no longer read are dropped
+                self.clear_sources(node_id);
+
+                let changed = self.with_observer(node_id, || computation.borrow().run(value));
+
+                // e.g. a memo which was tracked before it was computed for
+                // the first time
+                if self.node_height(node_id) > node.height {
+                    self.raise_subscriber_heights(node_id);
+                }
+
+                changed
             }
         };
 
@@ -316,71 +1238,189 @@ impl Runtime {
             return;
         }
 
-        // mark subscribers dirty
+        // mark subscribers dirty, except for the observer which is reading the
+        // node right now, and so sees the new value
         let subscribers = self.node_subscribers.borrow();
         let mut nodes = self.nodes.borrow_mut();
+        let observer = self.observer.get();
 
-        let Some(subscribers) = subscribers.get(node_id) else {
+        let Some(subscribers) = subscribers.get(self.key(node_id)) else {
             return;
         };
 
         for child_id in subscribers.borrow().iter() {
-            if let Some(node) = nodes.get_mut(*child_id) {
+            if observer == Some(*child_id) {
+                continue;
+            }
+
+            if let Some(node) = nodes.get_mut(self.key(*child_id)) {
                 node.state = NodeState::Dirty;
             }
         }
     }
 
+    /// Raises the heights of the node's subscribers, transitively, so that
+    /// they stay above the node after its height has grown.
+    fn raise_subscriber_heights(&self, node_id: NodeId) {
+        let subscribers = self.node_subscribers.borrow();
+        let mut nodes = self.nodes.borrow_mut();
+
+        let mut stack = vec![node_id];
+        while let Some(id) = stack.pop() {
+            let Some(height) = nodes.get(self.key(id)).map(|v| v.height) else {
+                continue;
+            };
+            let Some(subscribers) = subscribers.get(self.key(id)) else {
+                continue;
+            };
+
+            for &subscriber in subscribers.borrow().iter() {
+                if let Some(node) = nodes.get_mut(self.key(subscriber)) {
+                    if node.height <= height {
+                        node.height = height + 1;
+                        stack.push(subscriber);
+                    }
+                }
+            }
+        }
+    }
+
     /// Tracks the given node as a source of the current observer (e.g. a signal
     /// is tracked inside an effect).
     ///
     /// Since the mapping is bidirectional (there's `node_sources` and
     /// `node_subscribers`), both maps will be updated.
-    pub fn track(&self, node_id: NodeId) {
+    ///
+    /// Returns whether the node has become a source of the observer, i.e.
+    /// whether it wasn't read during the current run of the observer yet.
+    pub fn track(&self, node_id: NodeId) -> bool {
         let Some(observer) = self.observer.get() else {
-            return;
+            return false;
+        };
+
+        // a signal written since it was last pulled marks its subscribers
+        // dirty once it's settled, so it's settled before the observer
+        // subscribes to it: the observer is running, and sees the new value
+        if self.node_state(node_id) == NodeState::DirtyMarked {
+            self.update(node_id);
+            self.mark_clean(node_id);
+        }
+
+        let height = self.nodes.borrow().get(self.key(node_id)).map(|v| v.height);
+        let Some(height) = height else {
+            return false;
         };
 
         let mut subscribers = self.node_subscribers.borrow_mut();
-        if let Some(subscribers) = subscribers.entry(node_id) {
-            subscribers.or_default().borrow_mut().insert(observer);
+        let Some(subscribers) = subscribers.entry(self.key(node_id)) else {
+            return false;
+        };
+
+        // the set of subscribers tells whether the node was already read
+        // during this run
+        if !subscribers.or_default().borrow_mut().insert(observer) {
+            return false;
         }
 
         let mut sources = self.node_sources.borrow_mut();
-        if let Some(sources) = sources.entry(observer) {
-            sources.or_default().borrow_mut().insert(node_id);
+        if let Some(sources) = sources.entry(self.key(observer)) {
+            sources.or_default().borrow_mut().push(node_id);
         }
+
+        // the observer is above all of its sources
+        if let Some(node) = self.nodes.borrow_mut().get_mut(self.key(observer)) {
+            node.height = node.height.max(height + 1);
+        }
+
+        true
     }
 
-    fn cleanup_children(&self, node_id: NodeId) {
-        let mut children = self.node_children.borrow_mut();
-        let Some(children) = children.remove(node_id) else {
+    /// Returns whether there's a current observer, i.e. whether reads are
+    /// tracked.
+    pub fn is_tracking(&self) -> bool {
+        self.observer.get().is_some()
+    }
+
+    /// Removes all of the node's sources, unsubscribing it from their updates.
+    ///
+    /// The height of the node is reset, and computed again as the sources are
+    /// tracked.
+    fn clear_sources(&self, node_id: NodeId) {
+        if let Some(node) = self.nodes.borrow_mut().get_mut(self.key(node_id)) {
+            node.height = 0;
+        }
+
+        let sources = self.node_sources.borrow_mut().remove(self.key(node_id));
+        let Some(sources) = sources else {
             return;
         };
 
-        for child in children.into_inner() {
-            self.cleanup_children(child);
+        for source in sources.into_inner() {
+            if let Some(sub) = self.node_subscribers.borrow().get(self.key(source)) {
+                sub.borrow_mut().remove(&node_id);
+            }
+        }
+    }
 
-            let subscribers = self.node_subscribers.borrow_mut().remove(child);
-            if let Some(subscribers) = subscribers {
-                for sub in subscribers.into_inner() {
-                    if let Some(source) = self.node_sources.borrow_mut().get(sub) {
-                        source.borrow_mut().remove(&child);
-                    }
+    /// Disposes the node along with all of its descendants in the ownership
+    /// hierarchy.
+    pub fn dispose_node(&self, node_id: NodeId) {
+        self.cleanup_children(node_id);
+
+        let subscribers = self.node_subscribers.borrow_mut().remove(self.key(node_id));
+        if let Some(subscribers) = subscribers {
+            for sub in subscribers.into_inner() {
+                if let Some(sources) = self.node_sources.borrow().get(self.key(sub)) {
+                    sources.borrow_mut().retain(|&id| id != node_id);
                 }
             }
+        }
 
-            let sources = self.node_sources.borrow_mut().remove(child);
-            if let Some(sources) = sources {
-                for source in sources.into_inner() {
-                    if let Some(sub) = self.node_subscribers.borrow_mut().get(source) {
-                        sub.borrow_mut().remove(&child);
-                    }
-                }
+        self.clear_sources(node_id);
+
+        let parent = self.node_parents.borrow_mut().remove(self.key(node_id));
+        if let Some(parent) = parent {
+            if let Some(children) = self.node_children.borrow().get(self.key(parent)) {
+                children.borrow_mut().remove(&node_id);
             }
+        }
+
+        #[cfg(debug_assertions)]
+        {
+            self.roots.borrow_mut().remove(&node_id);
+            self.reported_leaks.borrow_mut().remove(&node_id);
+        }
 
-            self.node_parents.borrow_mut().remove(child);
-            self.nodes.borrow_mut().remove(child);
+        // the value may run arbitrary code when dropped, so make sure that
+        // `nodes` is not borrowed at that point
+        let node = self.nodes.borrow_mut().remove(self.key(node_id));
+        drop(node);
+    }
+
+    /// Runs the node's cleanup callbacks in reverse order, drops provided
+    /// contexts and disposes all of its children, e.g. before re-running an
+    /// effect.
+    fn cleanup_children(&self, node_id: NodeId) {
+        let contexts = self.node_contexts.borrow_mut().remove(self.key(node_id));
+        drop(contexts);
+
+        let cleanups = self.node_cleanups.borrow_mut().remove(self.key(node_id));
+        if let Some(cleanups) = cleanups {
+            // cleanups must not be tracked by whatever is being updated now
+            self.untrack(|| {
+                for cleanup in cleanups.into_iter().rev() {
+                    cleanup();
+                }
+            });
+        }
+
+        let children = self.node_children.borrow_mut().remove(self.key(node_id));
+        let Some(children) = children else {
+            return;
+        };
+
+        for child in children.into_inner() {
+            self.dispose_node(child);
         }
     }
 }
@@ -0,0 +1,45 @@
+use std::panic::Location;
+
+use crate::node::NodeId;
+use crate::runtime::with_runtime;
+
+/// Creates a new root scope and runs the closure inside of it.
+///
+/// All nodes created inside the closure are owned by the root, and live until
+/// it is disposed with [`Scope::dispose`].
+#[track_caller]
+pub fn create_root<Ret>(func: impl FnOnce(Scope) -> Ret) -> Ret {
+    let location = Location::caller();
+    with_runtime(|runtime| {
+        runtime.create_root(|id| {
+            runtime.set_node_location(id, location);
+            func(Scope { id })
+        })
+    })
+}
+
+/// Registers a callback which will be run when the current scope is disposed,
+/// or before the current effect is re-run.
+///
+/// Callbacks are run in reverse order of registration.
+pub fn on_cleanup(func: impl FnOnce() + 'static) {
+    with_runtime(|runtime| runtime.on_cleanup(Box::new(func)));
+}
+
+/// Owner of reactive nodes.
+#[derive(Debug, Clone, Copy)]
+pub struct Scope {
+    id: NodeId,
+}
+
+impl Scope {
+    /// Returns the id of the scope node, the parent of the nodes created in it.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Disposes the scope and all of the nodes it owns, recursively.
+    pub fn dispose(self) {
+        with_runtime(|runtime| runtime.dispose_root(self.id));
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::panic::Location;
+
+use ahash::AHashMap;
+
+use crate::effect::create_effect;
+use crate::error::ReactiveError;
+use crate::node::{wrap_value, NodeId, Value};
+use crate::runtime::{with_runtime, RuntimeInner};
+use crate::scope::on_cleanup;
+
+#[track_caller]
+pub fn create_selector<T>(source: impl Fn() -> T + 'static) -> Selector<T>
+where
+    T: Clone + Eq + Hash + Value,
+{
+    Selector::new(source)
+}
+
+/// Reactive "is selected" query over a source value.
+///
+/// `selector.selected(&key)` is equivalent to `source() == key`, but when the
+/// source changes, only the readers of the previous and the current key are
+/// notified, instead of the readers of every key.
+pub struct Selector<T> {
+    id: NodeId,
+    marker: PhantomData<T>,
+}
+
+struct SelectorInner<T> {
+    /// Current value of the source, `None` until the source is first run.
+    value: Option<T>,
+
+    /// Scope which owns the selector. Lazily created key nodes are assigned to
+    /// it, so that they're disposed together with the selector.
+    owner: Option<NodeId>,
+
+    /// Nodes tracked by the readers of the corresponding keys.
+    keys: AHashMap<T, KeyNode>,
+}
+
+struct KeyNode {
+    node: NodeId,
+
+    /// Number of observers which have read the key since they were last run.
+    /// The node is disposed once the last of them is re-run or disposed.
+    readers: usize,
+}
+
+impl<T: Clone + Eq + Hash + Value> Selector<T> {
+    #[track_caller]
+    pub fn new(source: impl Fn() -> T + 'static) -> Selector<T> {
+        let location = Location::caller();
+        let id = with_runtime(|runtime| {
+            let value = wrap_value(SelectorInner::<T> {
+                value: None,
+                owner: runtime.current_scope(),
+                keys: AHashMap::new(),
+            });
+
+            let id = runtime.create_signal(value);
+            runtime.set_node_location(id, location);
+            id
+        });
+
+        let selector = Selector {
+            id,
+            marker: PhantomData,
+        };
+
+        create_effect(move |_| {
+            let value = source();
+            with_runtime(|runtime| selector.notify(runtime, value));
+        });
+
+        selector
+    }
+
+    /// Returns whether the source is equal to `key`, tracking only that key as
+    /// a source of the current observer.
+    pub fn selected(&self, key: &T) -> bool {
+        with_runtime(|runtime| {
+            let tracking = runtime.is_tracking();
+            let result = self.with_inner(runtime, |inner| {
+                let node = match inner.keys.get(key) {
+                    Some(key_node) => Some(key_node.node),
+                    None if tracking => {
+                        let node = runtime.with_scope(inner.owner, || runtime.create_trigger());
+                        let key_node = KeyNode { node, readers: 0 };
+                        inner.keys.insert(key.clone(), key_node);
+                        Some(node)
+                    }
+                    None => None,
+                };
+
+                (node, inner.value.as_ref() == Some(key))
+            });
+
+            let (node, selected) = result.unwrap_or_else(|err| panic!("{err}"));
+            if let Some(node) = node {
+                if runtime.track(node) {
+                    self.add_reader(runtime, key.clone(), node);
+                }
+            }
+
+            selected
+        })
+    }
+
+    /// Counts the current observer as a reader of the key, until it's re-run
+    /// or disposed.
+    fn add_reader(&self, runtime: &RuntimeInner, key: T, node: NodeId) {
+        let _ = self.with_inner(runtime, |inner| {
+            if let Some(key_node) = inner.keys.get_mut(&key) {
+                key_node.readers += 1;
+            }
+        });
+
+        let selector = *self;
+        on_cleanup(move || {
+            with_runtime(|runtime| selector.remove_reader(runtime, &key, node));
+        });
+    }
+
+    /// Disposes the node of the key once it has no readers left.
+    fn remove_reader(&self, runtime: &RuntimeInner, key: &T, node: NodeId) {
+        let unused = self.with_inner(runtime, |inner| {
+            let Some(key_node) = inner.keys.get_mut(key) else {
+                return false;
+            };
+
+            // the key may have a new node by now
+            if key_node.node != node {
+                return false;
+            }
+
+            key_node.readers -= 1;
+            if key_node.readers > 0 {
+                return false;
+            }
+
+            inner.keys.remove(key);
+            true
+        });
+
+        // the selector may have been disposed before its readers
+        if let Ok(true) = unused {
+            runtime.dispose_node(node);
+        }
+    }
+
+    /// Stores the new source value and notifies the readers of the previous
+    /// and the new key.
+    fn notify(&self, runtime: &RuntimeInner, value: T) {
+        let nodes = self.with_inner(runtime, |inner| {
+            let prev = inner.value.replace(value);
+            if prev == inner.value {
+                return Vec::new();
+            }
+
+            let keys = prev.iter().chain(inner.value.iter());
+            let key_nodes = keys.filter_map(|key| inner.keys.get(key));
+            key_nodes.map(|key_node| key_node.node).collect()
+        });
+
+        let Ok(nodes) = nodes else {
+            return;
+        };
+
+        runtime.batch(|| {
+            for node in nodes {
+                runtime.mark_descendants_dirty(node);
+            }
+        });
+    }
+
+    /// Returns the number of keys which currently have a node, i.e. which are
+    /// read by at least one observer.
+    pub fn tracked_keys(&self) -> usize {
+        with_runtime(|runtime| self.with_inner(runtime, |inner| inner.keys.len()))
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn with_inner<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut SelectorInner<T>) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        runtime.with_node_value_mut::<T, _, _>(self.id, func)
+    }
+}
+
+impl<T> fmt::Debug for Selector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Selector({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for Selector<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Selector<T> {}
@@ -1,28 +1,133 @@
 use std::fmt;
 use std::marker::PhantomData;
+use std::panic::Location;
+use std::rc::Rc;
+use std::sync::mpsc;
 
-use super::node::{wrap_value, NodeId};
-use super::runtime::with_runtime;
+use super::error::ReactiveError;
+use super::node::{wrap_equality, wrap_value, Equality, NodeId, Value};
+use super::runtime::{with_runtime, ExternalUpdate};
+use super::traits::{SignalSet, SignalUpdate, SignalWith};
 
-pub fn create_signal<T: 'static>(value: T) -> Signal<T> {
+#[track_caller]
+pub fn create_signal<T: Value>(value: T) -> Signal<T> {
     Signal::new(value)
 }
 
+#[track_caller]
+pub fn create_signal_with_options<T: Value>(value: T, options: SignalOptions<T>) -> Signal<T> {
+    Signal::new_with_options(value, options)
+}
+
+/// Creates a signal and splits it into separate read and write handles.
+#[track_caller]
+pub fn create_signal_split<T: Value>(value: T) -> (ReadSignal<T>, WriteSignal<T>) {
+    Signal::new(value).split()
+}
+
+/// Options deciding when the subscribers of a signal or a memo are notified.
+pub struct SignalOptions<T> {
+    equality: Option<Equality<T>>,
+}
+
+impl<T: Value> SignalOptions<T> {
+    /// Notify the subscribers on every write, even if the value is unchanged.
+    /// This is the default for signals.
+    pub fn always_notify() -> SignalOptions<T> {
+        SignalOptions { equality: None }
+    }
+
+    /// Skip notifying the subscribers if the new value is equal to the old
+    /// one. This is the default for memos.
+    pub fn dedup() -> SignalOptions<T>
+    where
+        T: PartialEq,
+    {
+        SignalOptions::equality(T::eq)
+    }
+
+    /// Skip notifying the subscribers if the given comparator returns `true`
+    /// for the old and the new value.
+    pub fn equality(func: impl Fn(&T, &T) -> bool + 'static) -> SignalOptions<T> {
+        SignalOptions {
+            equality: Some(Rc::new(func)),
+        }
+    }
+
+    pub(crate) fn into_equality(self) -> Option<Equality<T>> {
+        self.equality
+    }
+}
+
+impl<T: Value> Default for SignalOptions<T> {
+    fn default() -> Self {
+        SignalOptions::always_notify()
+    }
+}
+
+impl<T> Clone for SignalOptions<T> {
+    fn clone(&self) -> Self {
+        SignalOptions {
+            equality: self.equality.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SignalOptions<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalOptions")
+            .field("dedup", &self.equality.is_some())
+            .finish()
+    }
+}
+
 pub struct Signal<T> {
     id: NodeId,
     marker: PhantomData<T>,
 }
 
-impl<T: 'static> Signal<T> {
+impl<T: Value> Signal<T> {
+    #[track_caller]
     pub fn new(value: T) -> Signal<T> {
+        Signal::new_with_options(value, SignalOptions::default())
+    }
+
+    #[track_caller]
+    pub fn new_with_options(value: T, options: SignalOptions<T>) -> Signal<T> {
+        let location = Location::caller();
         let value = wrap_value(value);
-        let id = with_runtime(|runtime| runtime.create_signal(value));
+        let equality = options.into_equality().map(wrap_equality);
+        let id = with_runtime(|runtime| {
+            let id = runtime.create_signal_with_equality(value, equality);
+            runtime.set_node_location(id, location);
+            id
+        });
         Signal {
             id,
             marker: PhantomData,
         }
     }
 
+    /// Returns the id of the signal node, which is subscribed to by readers.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Returns a handle which can only be used for reading the signal.
+    pub fn read_only(self) -> ReadSignal<T> {
+        ReadSignal { signal: self }
+    }
+
+    /// Returns a handle which can only be used for writing to the signal.
+    pub fn write_only(self) -> WriteSignal<T> {
+        WriteSignal { signal: self }
+    }
+
+    /// Splits the signal into separate read and write handles.
+    pub fn split(self) -> (ReadSignal<T>, WriteSignal<T>) {
+        (self.read_only(), self.write_only())
+    }
+
     pub fn get(&self) -> T
     where
         T: Clone,
@@ -31,6 +136,14 @@ impl<T: 'static> Signal<T> {
         self.get_untracked()
     }
 
+    pub fn try_get(&self) -> Result<T, ReactiveError>
+    where
+        T: Clone,
+    {
+        self.track();
+        self.try_get_untracked()
+    }
+
     pub fn get_untracked(&self) -> T
     where
         T: Clone,
@@ -38,58 +151,156 @@ impl<T: 'static> Signal<T> {
         self.with_untracked(T::clone)
     }
 
+    pub fn try_get_untracked(&self) -> Result<T, ReactiveError>
+    where
+        T: Clone,
+    {
+        self.try_with_untracked(T::clone)
+    }
+
     pub fn with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
         self.track();
         self.with_untracked(func)
     }
 
+    pub fn try_with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Result<Ret, ReactiveError> {
+        self.track();
+        self.try_with_untracked(func)
+    }
+
     pub fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.try_with_untracked(func)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_with_untracked<Ret>(
+        &self,
+        func: impl FnOnce(&T) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
         with_runtime(|runtime| {
-            let value = runtime.get_node_value(self.id)?;
-            let borrow = value.borrow();
-            let casted = borrow.downcast_ref::<T>()?;
-            Some(func(casted))
+            let value = runtime
+                .get_node_value(self.id)
+                .ok_or_else(|| ReactiveError::missing::<T>(runtime, self.id))?;
+            let borrow = value
+                .try_borrow()
+                .ok_or_else(|| ReactiveError::reentrant_borrow::<T>(self.id))?;
+            let casted = borrow
+                .downcast_ref::<T>()
+                .ok_or_else(|| ReactiveError::type_mismatch::<T>(self.id))?;
+            Ok(func(casted))
         })
-        .unwrap()
     }
 
     pub fn track(&self) {
         with_runtime(|runtime| runtime.track(self.id));
     }
 
+    /// Replaces the value, returning the old one.
+    ///
+    /// If the signal was created with a comparator and the values are equal,
+    /// the subscribers aren't notified.
     pub fn set(&self, value: T) -> T {
-        self.update(|v| std::mem::replace(v, value))
+        self.try_set(value).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_set(&self, value: T) -> Result<T, ReactiveError> {
+        let equality = with_runtime(|runtime| runtime.node_equality(self.id));
+        let (old_value, changed) = self.try_update_untracked(|v| {
+            let changed = !equality.is_some_and(|equality| equality(v, &value));
+            (std::mem::replace(v, value), changed)
+        })?;
+
+        if changed {
+            self.notify();
+        }
+
+        Ok(old_value)
     }
 
     pub fn set_untracked(&self, value: T) -> T {
         self.update_untracked(|v| std::mem::replace(v, value))
     }
 
+    pub fn try_set_untracked(&self, value: T) -> Result<T, ReactiveError> {
+        self.try_update_untracked(|v| std::mem::replace(v, value))
+    }
+
+    /// Modifies the value in place. Since the old value isn't kept around,
+    /// the subscribers are always notified.
     pub fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        self.try_update(func).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Result<Ret, ReactiveError> {
+        let ret = self.try_update_untracked(func)?;
+        self.notify();
+        Ok(ret)
+    }
+
+    /// Returns a handle for setting the signal from other threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signal is owned by a runtime other than the current one.
+    pub fn sender(&self) -> SignalSender<T>
+    where
+        T: Send,
+    {
+        self.try_sender().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_sender(&self) -> Result<SignalSender<T>, ReactiveError>
+    where
+        T: Send,
+    {
         with_runtime(|runtime| {
-            let ret = {
-                let value = runtime.get_node_value(self.id)?;
-                let mut borrow = value.borrow_mut();
-                let casted = borrow.downcast_mut::<T>()?;
-                func(casted)
-            };
+            if !runtime.owns(self.id) {
+                return Err(ReactiveError::missing::<T>(runtime, self.id));
+            }
+
+            Ok(SignalSender {
+                signal: *self,
+                sender: runtime.external_sender(),
+            })
+        })
+    }
 
+    /// Notifies the subscribers without modifying the value, e.g. after it was
+    /// changed with `update_untracked`.
+    pub fn notify(&self) {
+        with_runtime(|runtime| {
             runtime.mark_descendants_dirty(self.id);
             runtime.run_effects();
-
-            Some(ret)
-        })
-        .unwrap()
+        });
     }
 
     pub fn update_untracked<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
-        with_runtime(|runtime| {
-            let value = runtime.get_node_value(self.id)?;
-            let mut borrow = value.borrow_mut();
-            let casted = borrow.downcast_mut::<T>()?;
-            Some(func(casted))
-        })
-        .unwrap()
+        self.try_update_untracked(func)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_update_untracked<Ret>(
+        &self,
+        func: impl FnOnce(&mut T) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        let ret = with_runtime(|runtime| {
+            let value = runtime
+                .get_node_value(self.id)
+                .ok_or_else(|| ReactiveError::missing::<T>(runtime, self.id))?;
+            let mut borrow = value
+                .try_borrow_mut()
+                .ok_or_else(|| ReactiveError::reentrant_borrow::<T>(self.id))?;
+            let casted = borrow
+                .downcast_mut::<T>()
+                .ok_or_else(|| ReactiveError::type_mismatch::<T>(self.id))?;
+            Ok(func(casted))
+        })?;
+
+        // called once the value is no longer borrowed, so that it can be read
+        #[cfg(feature = "serde")]
+        with_runtime(|runtime| runtime.notify_write(self.id));
+
+        Ok(ret)
     }
 }
 
@@ -106,3 +317,239 @@ impl<T> Clone for Signal<T> {
 }
 
 impl<T> Copy for Signal<T> {}
+
+impl<T: Value> SignalWith for Signal<T> {
+    type Value = T;
+
+    fn track(&self) {
+        Signal::track(self)
+    }
+
+    fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        Signal::with_untracked(self, func)
+    }
+}
+
+impl<T: Value> SignalUpdate for Signal<T> {
+    type Value = T;
+
+    fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        Signal::update(self, func)
+    }
+
+    fn update_untracked<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        Signal::update_untracked(self, func)
+    }
+}
+
+impl<T: Value> SignalSet for Signal<T> {
+    fn set(&self, value: T) -> T {
+        Signal::set(self, value)
+    }
+
+    fn set_untracked(&self, value: T) -> T {
+        Signal::set_untracked(self, value)
+    }
+}
+
+/// Read-only handle of a signal.
+pub struct ReadSignal<T> {
+    signal: Signal<T>,
+}
+
+impl<T: Value> ReadSignal<T> {
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.signal.get()
+    }
+
+    pub fn try_get(&self) -> Result<T, ReactiveError>
+    where
+        T: Clone,
+    {
+        self.signal.try_get()
+    }
+
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.signal.get_untracked()
+    }
+
+    pub fn try_with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Result<Ret, ReactiveError> {
+        self.signal.try_with(func)
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.signal.with(func)
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.signal.with_untracked(func)
+    }
+
+    pub fn track(&self) {
+        self.signal.track()
+    }
+}
+
+impl<T: Value> SignalWith for ReadSignal<T> {
+    type Value = T;
+
+    fn track(&self) {
+        ReadSignal::track(self)
+    }
+
+    fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        ReadSignal::with_untracked(self, func)
+    }
+}
+
+impl<T> From<Signal<T>> for ReadSignal<T> {
+    fn from(signal: Signal<T>) -> Self {
+        ReadSignal { signal }
+    }
+}
+
+impl<T> fmt::Debug for ReadSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ReadSignal({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for ReadSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ReadSignal<T> {}
+
+/// Write-only handle of a signal.
+pub struct WriteSignal<T> {
+    signal: Signal<T>,
+}
+
+impl<T: Value> WriteSignal<T> {
+    pub fn set(&self, value: T) -> T {
+        self.signal.set(value)
+    }
+
+    pub fn try_set(&self, value: T) -> Result<T, ReactiveError> {
+        self.signal.try_set(value)
+    }
+
+    pub fn set_untracked(&self, value: T) -> T {
+        self.signal.set_untracked(value)
+    }
+
+    pub fn try_update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Result<Ret, ReactiveError> {
+        self.signal.try_update(func)
+    }
+
+    pub fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        self.signal.update(func)
+    }
+
+    pub fn update_untracked<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        self.signal.update_untracked(func)
+    }
+
+    pub fn notify(&self) {
+        self.signal.notify()
+    }
+}
+
+impl<T: Value> SignalUpdate for WriteSignal<T> {
+    type Value = T;
+
+    fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        WriteSignal::update(self, func)
+    }
+
+    fn update_untracked<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        WriteSignal::update_untracked(self, func)
+    }
+}
+
+impl<T: Value> SignalSet for WriteSignal<T> {
+    fn set(&self, value: T) -> T {
+        WriteSignal::set(self, value)
+    }
+
+    fn set_untracked(&self, value: T) -> T {
+        WriteSignal::set_untracked(self, value)
+    }
+}
+
+impl<T> From<Signal<T>> for WriteSignal<T> {
+    fn from(signal: Signal<T>) -> Self {
+        WriteSignal { signal }
+    }
+}
+
+impl<T> fmt::Debug for WriteSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WriteSignal({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for WriteSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WriteSignal<T> {}
+
+/// Handle for setting a signal from other threads, e.g. from file watchers or
+/// network threads.
+///
+/// The writes are queued, and applied once the runtime owning the signal calls
+/// [`Runtime::drain_external_updates`](crate::Runtime::drain_external_updates).
+pub struct SignalSender<T> {
+    signal: Signal<T>,
+    sender: mpsc::Sender<ExternalUpdate>,
+}
+
+impl<T: Value + Send> SignalSender<T> {
+    /// Queues setting the value. Returns `false` if the runtime owning the
+    /// signal has been dropped.
+    pub fn send(&self, value: T) -> bool {
+        let signal = self.signal;
+        self.send_with(move || {
+            // the signal may have been disposed in the meantime
+            let _ = signal.try_set(value);
+        })
+    }
+
+    /// Queues modifying the value in place, see [`SignalSender::send`].
+    pub fn send_update(&self, func: impl FnOnce(&mut T) + Send + 'static) -> bool {
+        let signal = self.signal;
+        self.send_with(move || {
+            let _ = signal.try_update(func);
+        })
+    }
+
+    fn send_with(&self, update: impl FnOnce() + Send + 'static) -> bool {
+        self.sender.send(Box::new(update)).is_ok()
+    }
+}
+
+impl<T> fmt::Debug for SignalSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SignalSender({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for SignalSender<T> {
+    fn clone(&self) -> Self {
+        SignalSender {
+            signal: self.signal,
+            sender: self.sender.clone(),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::panic::Location;
+
+use ahash::{AHashMap, AHashSet};
+use slotmap::SlotMap;
+
+use crate::error::ReactiveError;
+use crate::node::{wrap_value, NodeId, Shared, Value, ValueCell};
+use crate::runtime::{with_runtime, RuntimeInner};
+use crate::scope::on_cleanup;
+
+#[track_caller]
+pub fn create_signal_map<K, V>(entries: impl IntoIterator<Item = (K, V)>) -> SignalMap<K, V>
+where
+    K: Clone + Eq + Hash + Value,
+    V: Clone + Value,
+{
+    SignalMap::new(entries)
+}
+
+/// Change of a [`SignalMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapDiff<K, V> {
+    Insert { key: K, value: V },
+    Remove { key: K },
+    Update { key: K, value: V },
+    Clear,
+}
+
+impl<K: Eq + Hash, V> MapDiff<K, V> {
+    /// Applies the change to a map.
+    pub fn apply(self, entries: &mut AHashMap<K, V>) {
+        match self {
+            MapDiff::Insert { key, value } | MapDiff::Update { key, value } => {
+                entries.insert(key, value);
+            }
+            MapDiff::Remove { key } => {
+                entries.remove(&key);
+            }
+            MapDiff::Clear => entries.clear(),
+        }
+    }
+}
+
+slotmap::new_key_type! {
+    struct ListenerId;
+}
+
+#[cfg(not(feature = "sync"))]
+type Listener<K, V> = Shared<dyn Fn(&MapDiff<K, V>)>;
+
+#[cfg(feature = "sync")]
+type Listener<K, V> = Shared<dyn Fn(&MapDiff<K, V>) + Send + Sync>;
+
+struct MapState<K, V> {
+    entries: AHashMap<K, V>,
+    listeners: SlotMap<ListenerId, Listener<K, V>>,
+}
+
+/// Reactive hash map which records structured changes.
+///
+/// Readers tracking the map are re-run on every change, while
+/// [`SignalMap::subscribe`] allows consuming the changes incrementally.
+pub struct SignalMap<K, V> {
+    id: NodeId,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<K, V> SignalMap<K, V>
+where
+    K: Clone + Eq + Hash + Value,
+    V: Clone + Value,
+{
+    #[track_caller]
+    pub fn new(entries: impl IntoIterator<Item = (K, V)>) -> SignalMap<K, V> {
+        let location = Location::caller();
+        let value = wrap_value(MapState {
+            entries: entries.into_iter().collect(),
+            listeners: SlotMap::<ListenerId, Listener<K, V>>::with_key(),
+        });
+        let id = with_runtime(|runtime| {
+            let id = runtime.create_signal(value);
+            runtime.set_node_location(id, location);
+            id
+        });
+        SignalMap {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.with(|entries| entries.get(key).cloned())
+    }
+
+    pub fn get_untracked(&self, key: &K) -> Option<V> {
+        self.with_untracked(|entries| entries.get(key).cloned())
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&AHashMap<K, V>) -> Ret) -> Ret {
+        self.track();
+        self.with_untracked(func)
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(&AHashMap<K, V>) -> Ret) -> Ret {
+        with_runtime(|runtime| self.with_state(runtime, |state| func(&state.entries)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.with(|entries| entries.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.with(|entries| entries.is_empty())
+    }
+
+    pub fn track(&self) {
+        with_runtime(|runtime| runtime.track(self.id));
+    }
+
+    /// Inserts an entry, returning the previous value.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let prev = self.get_untracked(&key);
+        if prev.is_some() {
+            self.apply(MapDiff::Update { key, value });
+        } else {
+            self.apply(MapDiff::Insert { key, value });
+        }
+        prev
+    }
+
+    /// Removes an entry, returning its value.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let prev = self.get_untracked(key)?;
+        self.apply(MapDiff::Remove { key: key.clone() });
+        Some(prev)
+    }
+
+    pub fn clear(&self) {
+        self.apply(MapDiff::Clear);
+    }
+
+    /// Applies the change, notifying the listeners and the readers.
+    pub fn apply(&self, diff: MapDiff<K, V>) {
+        with_runtime(|runtime| {
+            let listeners = self.with_state(runtime, |state| {
+                diff.clone().apply(&mut state.entries);
+                state.listeners.values().cloned().collect::<Vec<_>>()
+            });
+
+            runtime.batch(|| {
+                for listener in listeners {
+                    listener(&diff);
+                }
+
+                runtime.mark_descendants_dirty(self.id);
+            });
+        });
+    }
+
+    /// Registers a listener which is called on every change of the map.
+    ///
+    /// The listener is removed when the current scope is cleaned up.
+    pub fn subscribe(&self, listener: impl Fn(&MapDiff<K, V>) + Value) {
+        let this = *self;
+        let id = with_runtime(|runtime| {
+            self.with_state(runtime, |state| {
+                state.listeners.insert(Shared::new(listener))
+            })
+        });
+
+        on_cleanup(move || {
+            with_runtime(|runtime| {
+                // the map itself may already be disposed
+                let _ = this.try_with_state(runtime, |state| state.listeners.remove(id));
+            })
+        });
+    }
+
+    /// Returns a map with the function applied to each value, which is
+    /// updated incrementally.
+    pub fn map<U: Clone + Value>(&self, func: impl Fn(&K, &V) -> U + Value) -> SignalMap<K, U> {
+        let mapped = SignalMap::new(self.with_untracked(|entries| {
+            let iter = entries.iter();
+            iter.map(|(k, v)| (k.clone(), func(k, v)))
+                .collect::<Vec<_>>()
+        }));
+
+        self.subscribe(move |diff| {
+            mapped.apply(match diff {
+                MapDiff::Insert { key, value } => MapDiff::Insert {
+                    key: key.clone(),
+                    value: func(key, value),
+                },
+                MapDiff::Remove { key } => MapDiff::Remove { key: key.clone() },
+                MapDiff::Update { key, value } => MapDiff::Update {
+                    key: key.clone(),
+                    value: func(key, value),
+                },
+                MapDiff::Clear => MapDiff::Clear,
+            })
+        });
+
+        mapped
+    }
+
+    /// Returns a map with only the entries matching the predicate, which is
+    /// updated incrementally.
+    pub fn filter(&self, predicate: impl Fn(&K, &V) -> bool + Value) -> SignalMap<K, V> {
+        let filtered = SignalMap::new(self.with_untracked(|entries| {
+            let iter = entries.iter().filter(|(k, v)| predicate(k, v));
+            iter.map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+        }));
+
+        // keys of the included entries
+        let included = ValueCell::new(
+            filtered.with_untracked(|entries| entries.keys().cloned().collect::<AHashSet<_>>()),
+        );
+
+        self.subscribe(move |diff| {
+            let mut included = included.borrow_mut();
+
+            let diff = match diff {
+                MapDiff::Insert { key, value } | MapDiff::Update { key, value } => {
+                    let was = included.contains(key);
+                    let now = predicate(key, value);
+                    let key = key.clone();
+                    let value = value.clone();
+                    match (was, now) {
+                        (true, true) => Some(MapDiff::Update { key, value }),
+                        (true, false) => {
+                            included.remove(&key);
+                            Some(MapDiff::Remove { key })
+                        }
+                        (false, true) => {
+                            included.insert(key.clone());
+                            Some(MapDiff::Insert { key, value })
+                        }
+                        (false, false) => None,
+                    }
+                }
+                MapDiff::Remove { key } => included
+                    .remove(key)
+                    .then(|| MapDiff::Remove { key: key.clone() }),
+                MapDiff::Clear => {
+                    included.clear();
+                    Some(MapDiff::Clear)
+                }
+            };
+
+            if let Some(diff) = diff {
+                filtered.apply(diff);
+            }
+        });
+
+        filtered
+    }
+
+    fn with_state<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut MapState<K, V>) -> Ret,
+    ) -> Ret {
+        self.try_with_state(runtime, func)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_with_state<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut MapState<K, V>) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        runtime.with_node_value_mut::<AHashMap<K, V>, _, _>(self.id, func)
+    }
+}
+
+impl<K, V> fmt::Debug for SignalMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SignalMap({}, {})",
+            std::any::type_name::<K>(),
+            std::any::type_name::<V>()
+        )
+    }
+}
+
+impl<K, V> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for SignalMap<K, V> {}
@@ -0,0 +1,427 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::panic::Location;
+
+use slotmap::SlotMap;
+
+use crate::error::ReactiveError;
+use crate::node::{wrap_value, NodeId, Shared, Value, ValueCell};
+use crate::runtime::{with_runtime, RuntimeInner};
+use crate::scope::on_cleanup;
+
+#[track_caller]
+pub fn create_signal_vec<T: Clone + Value>(items: Vec<T>) -> SignalVec<T> {
+    SignalVec::new(items)
+}
+
+/// Change of a [`SignalVec`].
+///
+/// Indices refer to the state of the vector right before the change is
+/// applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VecDiff<T> {
+    Insert { index: usize, value: T },
+    Remove { index: usize },
+    Move { from: usize, to: usize },
+    Update { index: usize, value: T },
+    Clear,
+}
+
+impl<T> VecDiff<T> {
+    /// Applies the change to a vector.
+    pub fn apply(self, items: &mut Vec<T>) {
+        match self {
+            VecDiff::Insert { index, value } => items.insert(index, value),
+            VecDiff::Remove { index } => {
+                items.remove(index);
+            }
+            VecDiff::Move { from, to } => {
+                let value = items.remove(from);
+                items.insert(to, value);
+            }
+            VecDiff::Update { index, value } => items[index] = value,
+            VecDiff::Clear => items.clear(),
+        }
+    }
+}
+
+slotmap::new_key_type! {
+    struct ListenerId;
+}
+
+#[cfg(not(feature = "sync"))]
+type Listener<T> = Shared<dyn Fn(&VecDiff<T>)>;
+
+#[cfg(feature = "sync")]
+type Listener<T> = Shared<dyn Fn(&VecDiff<T>) + Send + Sync>;
+
+struct VecState<T> {
+    items: Vec<T>,
+    listeners: SlotMap<ListenerId, Listener<T>>,
+}
+
+/// Reactive vector which records structured changes.
+///
+/// Readers tracking the vector are re-run on every change, while
+/// [`SignalVec::subscribe`] allows consuming the changes incrementally.
+pub struct SignalVec<T> {
+    id: NodeId,
+    marker: PhantomData<T>,
+}
+
+impl<T: Clone + Value> SignalVec<T> {
+    #[track_caller]
+    pub fn new(items: Vec<T>) -> SignalVec<T> {
+        let location = Location::caller();
+        let value = wrap_value(VecState {
+            items,
+            listeners: SlotMap::<ListenerId, Listener<T>>::with_key(),
+        });
+        let id = with_runtime(|runtime| {
+            let id = runtime.create_signal(value);
+            runtime.set_node_location(id, location);
+            id
+        });
+        SignalVec {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn get(&self) -> Vec<T> {
+        self.with(<[T]>::to_vec)
+    }
+
+    pub fn get_untracked(&self) -> Vec<T> {
+        self.with_untracked(<[T]>::to_vec)
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&[T]) -> Ret) -> Ret {
+        self.track();
+        self.with_untracked(func)
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(&[T]) -> Ret) -> Ret {
+        with_runtime(|runtime| self.with_state(runtime, |state| func(&state.items)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.with(<[T]>::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.with(<[T]>::is_empty)
+    }
+
+    pub fn track(&self) {
+        with_runtime(|runtime| runtime.track(self.id));
+    }
+
+    pub fn push(&self, value: T) {
+        let index = self.with_untracked(<[T]>::len);
+        self.apply(VecDiff::Insert { index, value });
+    }
+
+    pub fn insert(&self, index: usize, value: T) {
+        self.apply(VecDiff::Insert { index, value });
+    }
+
+    pub fn remove(&self, index: usize) -> T {
+        let value = self.with_untracked(|items| items[index].clone());
+        self.apply(VecDiff::Remove { index });
+        value
+    }
+
+    pub fn move_item(&self, from: usize, to: usize) {
+        self.apply(VecDiff::Move { from, to });
+    }
+
+    pub fn set(&self, index: usize, value: T) {
+        self.apply(VecDiff::Update { index, value });
+    }
+
+    pub fn clear(&self) {
+        self.apply(VecDiff::Clear);
+    }
+
+    /// Applies the change, notifying the listeners and the readers.
+    pub fn apply(&self, diff: VecDiff<T>) {
+        with_runtime(|runtime| {
+            let listeners = self.with_state(runtime, |state| {
+                diff.clone().apply(&mut state.items);
+                state.listeners.values().cloned().collect::<Vec<_>>()
+            });
+
+            runtime.batch(|| {
+                for listener in listeners {
+                    listener(&diff);
+                }
+
+                runtime.mark_descendants_dirty(self.id);
+            });
+        });
+    }
+
+    /// Registers a listener which is called on every change of the vector.
+    ///
+    /// The listener is removed when the current scope is cleaned up.
+    pub fn subscribe(&self, listener: impl Fn(&VecDiff<T>) + Value) {
+        let this = *self;
+        let id = with_runtime(|runtime| {
+            self.with_state(runtime, |state| {
+                state.listeners.insert(Shared::new(listener))
+            })
+        });
+
+        on_cleanup(move || {
+            with_runtime(|runtime| {
+                // the vector itself may already be disposed
+                let _ = this.try_with_state(runtime, |state| state.listeners.remove(id));
+            })
+        });
+    }
+
+    /// Returns a vector with the function applied to each item, which is
+    /// updated incrementally.
+    pub fn map<U: Clone + Value>(&self, func: impl Fn(&T) -> U + Value) -> SignalVec<U> {
+        let mapped = SignalVec::new(self.with_untracked(|items| items.iter().map(&func).collect()));
+
+        self.subscribe(move |diff| {
+            mapped.apply(match diff {
+                VecDiff::Insert { index, value } => VecDiff::Insert {
+                    index: *index,
+                    value: func(value),
+                },
+                VecDiff::Remove { index } => VecDiff::Remove { index: *index },
+                VecDiff::Move { from, to } => VecDiff::Move {
+                    from: *from,
+                    to: *to,
+                },
+                VecDiff::Update { index, value } => VecDiff::Update {
+                    index: *index,
+                    value: func(value),
+                },
+                VecDiff::Clear => VecDiff::Clear,
+            })
+        });
+
+        mapped
+    }
+
+    /// Returns a vector with only the items matching the predicate, which is
+    /// updated incrementally.
+    pub fn filter(&self, predicate: impl Fn(&T) -> bool + Value) -> SignalVec<T> {
+        // whether each of the source items is included
+        let included =
+            self.with_untracked(|items| items.iter().map(&predicate).collect::<Vec<_>>());
+        let filtered = self.with_untracked(|items| {
+            let iter = items.iter().zip(&included);
+            iter.filter(|(_, &inc)| inc)
+                .map(|(v, _)| v.clone())
+                .collect()
+        });
+        let filtered = SignalVec::new(filtered);
+
+        let included = ValueCell::new(included);
+        let count = |included: &[bool]| included.iter().filter(|&&v| v).count();
+
+        self.subscribe(move |diff| {
+            let mut included = included.borrow_mut();
+
+            let diff = match diff {
+                VecDiff::Insert { index, value } => {
+                    let now = predicate(value);
+                    included.insert(*index, now);
+                    let index = count(&included[..*index]);
+                    now.then(|| VecDiff::Insert {
+                        index,
+                        value: value.clone(),
+                    })
+                }
+                VecDiff::Remove { index } => {
+                    let was = included.remove(*index);
+                    let index = count(&included[..*index]);
+                    was.then_some(VecDiff::Remove { index })
+                }
+                VecDiff::Move { from, to } => {
+                    let was = included.remove(*from);
+                    let from = count(&included[..*from]);
+                    included.insert(*to, was);
+                    let to = count(&included[..*to]);
+                    (was && from != to).then_some(VecDiff::Move { from, to })
+                }
+                VecDiff::Update { index, value } => {
+                    let was = included[*index];
+                    let now = predicate(value);
+                    included[*index] = now;
+                    let index = count(&included[..*index]);
+                    let value = value.clone();
+                    match (was, now) {
+                        (true, true) => Some(VecDiff::Update { index, value }),
+                        (true, false) => Some(VecDiff::Remove { index }),
+                        (false, true) => Some(VecDiff::Insert { index, value }),
+                        (false, false) => None,
+                    }
+                }
+                VecDiff::Clear => {
+                    included.clear();
+                    Some(VecDiff::Clear)
+                }
+            };
+
+            if let Some(diff) = diff {
+                filtered.apply(diff);
+            }
+        });
+
+        filtered
+    }
+
+    /// Returns a vector sorted with the comparator function, which is updated
+    /// incrementally. The sort is stable.
+    pub fn sort_by(&self, compare: impl Fn(&T, &T) -> Ordering + Value) -> SignalVec<T> {
+        let mut sorter = Sorter {
+            values: Vec::new(),
+            order: Vec::new(),
+            compare,
+        };
+
+        for (index, value) in self.get_untracked().into_iter().enumerate() {
+            sorter.insert(index, value);
+        }
+
+        let sorted = SignalVec::new(
+            sorter
+                .order
+                .iter()
+                .map(|&i| sorter.values[i].clone())
+                .collect(),
+        );
+
+        let sorter = ValueCell::new(sorter);
+        self.subscribe(move |diff| {
+            let mut sorter = sorter.borrow_mut();
+            match diff {
+                VecDiff::Insert { index, value } => {
+                    let pos = sorter.insert(*index, value.clone());
+                    sorted.apply(VecDiff::Insert {
+                        index: pos,
+                        value: value.clone(),
+                    });
+                }
+                VecDiff::Remove { index } => {
+                    let (pos, _) = sorter.remove(*index);
+                    sorted.apply(VecDiff::Remove { index: pos });
+                }
+                VecDiff::Move { from, to } => {
+                    let (from, value) = sorter.remove(*from);
+                    let to = sorter.insert(*to, value);
+                    if from != to {
+                        sorted.apply(VecDiff::Move { from, to });
+                    }
+                }
+                VecDiff::Update { index, value } => {
+                    let (from, _) = sorter.remove(*index);
+                    let to = sorter.insert(*index, value.clone());
+                    if from != to {
+                        sorted.apply(VecDiff::Move { from, to });
+                    }
+                    sorted.apply(VecDiff::Update {
+                        index: to,
+                        value: value.clone(),
+                    });
+                }
+                VecDiff::Clear => {
+                    sorter.values.clear();
+                    sorter.order.clear();
+                    sorted.apply(VecDiff::Clear);
+                }
+            }
+        });
+
+        sorted
+    }
+
+    fn with_state<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut VecState<T>) -> Ret,
+    ) -> Ret {
+        self.try_with_state(runtime, func)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn try_with_state<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut VecState<T>) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        runtime.with_node_value_mut::<Vec<T>, _, _>(self.id, func)
+    }
+}
+
+impl<T> fmt::Debug for SignalVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SignalVec({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SignalVec<T> {}
+
+/// State of the [`SignalVec::sort_by`] combinator.
+struct Sorter<T, F> {
+    /// Copy of the source items.
+    values: Vec<T>,
+
+    /// Indices of the source items, in the sorted order.
+    order: Vec<usize>,
+
+    compare: F,
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Sorter<T, F> {
+    /// Inserts a source item, returning its position in the sorted order.
+    fn insert(&mut self, index: usize, value: T) -> usize {
+        self.values.insert(index, value);
+
+        for i in &mut self.order {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+
+        let value = &self.values[index];
+        let pos = self
+            .order
+            .partition_point(|&i| match (self.compare)(&self.values[i], value) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => i < index,
+            });
+
+        self.order.insert(pos, index);
+        pos
+    }
+
+    /// Removes a source item, returning its former position in the sorted
+    /// order.
+    fn remove(&mut self, index: usize) -> (usize, T) {
+        let pos = self.order.iter().position(|&i| i == index).unwrap();
+        self.order.remove(pos);
+
+        for i in &mut self.order {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+
+        (pos, self.values.remove(index))
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::rc::Rc;
+
+use ahash::AHashMap;
+
+use crate::error::ReactiveError;
+use crate::node::{wrap_value, NodeId, Shared, Value};
+use crate::runtime::{with_runtime, RuntimeInner};
+
+#[track_caller]
+pub fn create_store<T: Value>(value: T) -> Store<T> {
+    Store::new(value)
+}
+
+/// Reactive struct with fine-grained change tracking.
+///
+/// Fields are accessed with methods generated by `#[derive(Store)]`, e.g.
+/// `store.user().name().set(..)`. Reading a field only subscribes to that
+/// field, and writing to a field only notifies the readers of that field, of
+/// its subfields, and of the fields containing it.
+///
+/// Accessors are only visible where the fields are:
+///
+/// ```compile_fail
+/// mod model {
+///     #[derive(Clone, cuite_reactive::Store)]
+///     pub struct Account {
+///         pub name: String,
+///         password: String,
+///     }
+/// }
+///
+/// use model::AccountStoreFields;
+///
+/// fn password(store: cuite_reactive::Store<model::Account>) -> String {
+///     store.password().get()
+/// }
+/// ```
+///
+/// Fields named like the methods of stores, or of the traits implemented for
+/// them, are rejected, since their accessors would be shadowed:
+///
+/// ```compile_fail
+/// #[derive(Clone, cuite_reactive::Store)]
+/// struct Conversion {
+///     into: String,
+/// }
+/// ```
+pub struct Store<T> {
+    id: NodeId,
+    marker: PhantomData<T>,
+}
+
+/// Path to a field inside a store, as a list of field indices.
+type StorePath = Shared<[u32]>;
+
+struct StoreInner<T> {
+    value: T,
+
+    /// Scope which owns the store. Lazily created field nodes are assigned to
+    /// it, so that they're disposed together with the store.
+    owner: Option<NodeId>,
+
+    /// Nodes tracked by the readers of the corresponding fields.
+    fields: AHashMap<StorePath, NodeId>,
+}
+
+impl<T: Value> Store<T> {
+    #[track_caller]
+    pub fn new(value: T) -> Store<T> {
+        let location = Location::caller();
+        let id = with_runtime(|runtime| {
+            let value = wrap_value(StoreInner {
+                value,
+                owner: runtime.current_scope(),
+                fields: AHashMap::new(),
+            });
+
+            let id = runtime.create_signal(value);
+            runtime.set_node_location(id, location);
+            id
+        });
+
+        Store {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a handle to the whole value of the store.
+    pub fn root(&self) -> StoreField<T, T> {
+        StoreField {
+            store: *self,
+            path: Shared::from([]),
+            read: Rc::new(|v| v),
+            write: Rc::new(|v| v),
+        }
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.root().get()
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.root().with(func)
+    }
+
+    pub fn set(&self, value: T) -> T {
+        self.root().set(value)
+    }
+
+    pub fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        self.root().update(func)
+    }
+
+    fn with_inner<Ret>(
+        &self,
+        runtime: &RuntimeInner,
+        func: impl FnOnce(&mut StoreInner<T>) -> Ret,
+    ) -> Result<Ret, ReactiveError> {
+        runtime.with_node_value_mut::<T, _, _>(self.id, func)
+    }
+}
+
+impl<T> fmt::Debug for Store<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Store({})", std::any::type_name::<T>())
+    }
+}
+
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Store<T> {}
+
+type FieldRead<R, T> = Rc<dyn Fn(&R) -> &T>;
+type FieldWrite<R, T> = Rc<dyn Fn(&mut R) -> &mut T>;
+
+/// Handle to a field of type `T` inside a store of type `R`.
+pub struct StoreField<R, T> {
+    store: Store<R>,
+    path: StorePath,
+    read: FieldRead<R, T>,
+    write: FieldWrite<R, T>,
+}
+
+impl<R: Value, T: Value> StoreField<R, T> {
+    /// Returns a handle to a subfield. Used by the code generated with
+    /// `#[derive(Store)]`.
+    #[doc(hidden)]
+    pub fn field<U: Value>(
+        &self,
+        index: u32,
+        read: fn(&T) -> &U,
+        write: fn(&mut T) -> &mut U,
+    ) -> StoreField<R, U> {
+        let parent_read = self.read.clone();
+        let parent_write = self.write.clone();
+
+        StoreField {
+            store: self.store,
+            path: self.path.iter().copied().chain([index]).collect(),
+            read: Rc::new(move |v| read(parent_read(v))),
+            write: Rc::new(move |v| write(parent_write(v))),
+        }
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.track();
+        self.get_untracked()
+    }
+
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    pub fn with<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        self.track();
+        self.with_untracked(func)
+    }
+
+    pub fn with_untracked<Ret>(&self, func: impl FnOnce(&T) -> Ret) -> Ret {
+        with_runtime(|runtime| {
+            self.store
+                .with_inner(runtime, |inner| func((self.read)(&inner.value)))
+        })
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Tracks the field as a source of the current observer.
+    pub fn track(&self) {
+        with_runtime(|runtime| {
+            let node = self.store.with_inner(runtime, |inner| {
+                if let Some(&node) = inner.fields.get(&self.path) {
+                    return node;
+                }
+
+                let node = runtime.with_scope(inner.owner, || runtime.create_trigger());
+                inner.fields.insert(self.path.clone(), node);
+                node
+            });
+
+            if let Ok(node) = node {
+                runtime.track(node);
+            }
+        });
+    }
+
+    pub fn set(&self, value: T) -> T {
+        self.update(|v| std::mem::replace(v, value))
+    }
+
+    pub fn set_untracked(&self, value: T) -> T {
+        self.update_untracked(|v| std::mem::replace(v, value))
+    }
+
+    /// Modifies the field, notifying the readers of this field, its
+    /// subfields, and the fields containing it.
+    pub fn update<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        with_runtime(|runtime| {
+            let (ret, nodes) = self
+                .store
+                .with_inner(runtime, |inner| {
+                    let ret = func((self.write)(&mut inner.value));
+
+                    let nodes = inner
+                        .fields
+                        .iter()
+                        .filter(|(path, _)| {
+                            path.starts_with(&self.path) || self.path.starts_with(path)
+                        })
+                        .map(|(_, &node)| node)
+                        .collect::<Vec<_>>();
+
+                    (ret, nodes)
+                })
+                .unwrap_or_else(|err| panic!("{err}"));
+
+            runtime.batch(|| {
+                for node in nodes {
+                    runtime.mark_descendants_dirty(node);
+                }
+            });
+
+            ret
+        })
+    }
+
+    pub fn update_untracked<Ret>(&self, func: impl FnOnce(&mut T) -> Ret) -> Ret {
+        with_runtime(|runtime| {
+            self.store
+                .with_inner(runtime, |inner| func((self.write)(&mut inner.value)))
+        })
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl<R, T> fmt::Debug for StoreField<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "StoreField({}, {:?})",
+            std::any::type_name::<T>(),
+            self.path
+        )
+    }
+}
+
+impl<R, T> Clone for StoreField<R, T> {
+    fn clone(&self) -> Self {
+        StoreField {
+            store: self.store,
+            path: self.path.clone(),
+            read: self.read.clone(),
+            write: self.write.clone(),
+        }
+    }
+}
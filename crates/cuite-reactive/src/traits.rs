@@ -0,0 +1,63 @@
+/// Reactive value which can be borrowed.
+pub trait SignalWith {
+    type Value;
+
+    /// Tracks the value as a source of the current observer.
+    fn track(&self);
+
+    /// Borrows the value without tracking it.
+    fn with_untracked<Ret>(&self, func: impl FnOnce(&Self::Value) -> Ret) -> Ret;
+
+    /// Borrows the value, tracking it as a source of the current observer.
+    fn with<Ret>(&self, func: impl FnOnce(&Self::Value) -> Ret) -> Ret {
+        self.track();
+        self.with_untracked(func)
+    }
+}
+
+/// Reactive value which can be cloned out.
+///
+/// Implemented for all [`SignalWith`] types with a [`Clone`] value.
+pub trait SignalGet: SignalWith {
+    /// Clones the value, tracking it as a source of the current observer.
+    fn get(&self) -> Self::Value;
+
+    /// Clones the value without tracking it.
+    fn get_untracked(&self) -> Self::Value;
+}
+
+impl<S> SignalGet for S
+where
+    S: SignalWith,
+    S::Value: Clone,
+{
+    fn get(&self) -> Self::Value {
+        self.with(Clone::clone)
+    }
+
+    fn get_untracked(&self) -> Self::Value {
+        self.with_untracked(Clone::clone)
+    }
+}
+
+/// Reactive value which can be modified in place.
+pub trait SignalUpdate {
+    type Value;
+
+    /// Modifies the value and notifies the subscribers.
+    fn update<Ret>(&self, func: impl FnOnce(&mut Self::Value) -> Ret) -> Ret;
+
+    /// Modifies the value without notifying the subscribers.
+    fn update_untracked<Ret>(&self, func: impl FnOnce(&mut Self::Value) -> Ret) -> Ret;
+}
+
+/// Reactive value which can be replaced.
+pub trait SignalSet: SignalUpdate {
+    /// Replaces the value, notifying the subscribers unless the signal's
+    /// comparator considers the values equal. Returns the old value.
+    fn set(&self, value: Self::Value) -> Self::Value;
+
+    /// Replaces the value without notifying the subscribers. Returns the old
+    /// value.
+    fn set_untracked(&self, value: Self::Value) -> Self::Value;
+}
@@ -0,0 +1,54 @@
+use std::panic::Location;
+
+use crate::node::NodeId;
+use crate::runtime::with_runtime;
+
+#[track_caller]
+pub fn create_trigger() -> Trigger {
+    Trigger::new()
+}
+
+/// Signal without a value, used for notifying the subscribers about changes
+/// of data which lives outside of the reactive system.
+#[derive(Debug, Clone, Copy)]
+pub struct Trigger {
+    id: NodeId,
+}
+
+impl Trigger {
+    #[track_caller]
+    pub fn new() -> Trigger {
+        let location = Location::caller();
+        let id = with_runtime(|runtime| {
+            let id = runtime.create_trigger();
+            runtime.set_node_location(id, location);
+            id
+        });
+        Trigger { id }
+    }
+
+    /// Returns the id of the trigger node, a source without a value.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Tracks the trigger as a source of the current observer.
+    pub fn track(&self) {
+        with_runtime(|runtime| runtime.track(self.id));
+    }
+
+    /// Notifies the subscribers of the trigger.
+    pub fn notify(&self) {
+        with_runtime(|runtime| {
+            runtime.mark_descendants_dirty(self.id);
+            runtime.run_effects();
+        });
+    }
+}
+
+impl Default for Trigger {
+    #[track_caller]
+    fn default() -> Self {
+        Trigger::new()
+    }
+}
@@ -0,0 +1,99 @@
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use cuite_reactive::{batch, create_effect, create_signal};
+
+#[test]
+fn batch_runs_effects_once() {
+    let ops: Rc<RefCell<Vec<(i32, i32, i32)>>> = Default::default();
+
+    let a = create_signal(0);
+    let b = create_signal(0);
+    let c = create_signal(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push((a.get(), b.get(), c.get()));
+    });
+
+    batch(|| {
+        a.set(1);
+        b.set(2);
+        c.set(3);
+    });
+
+    assert_eq!(ops.borrow().as_slice(), &[(0, 0, 0), (1, 2, 3)]);
+}
+
+#[test]
+fn nested_batches() {
+    let ops: Rc<RefCell<Vec<(i32, i32)>>> = Default::default();
+
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push((a.get(), b.get()));
+    });
+
+    batch(|| {
+        a.set(1);
+
+        batch(|| b.set(2));
+        assert_eq!(ops.borrow().len(), 1);
+
+        a.set(3);
+    });
+
+    assert_eq!(ops.borrow().as_slice(), &[(0, 0), (3, 2)]);
+}
+
+#[test]
+fn batch_inside_effect() {
+    let ops: Rc<RefCell<Vec<(i32, i32)>>> = Default::default();
+
+    let source = create_signal(1);
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push((a.get(), b.get()));
+    });
+
+    create_effect(move |_| {
+        let value = source.get();
+        batch(|| {
+            a.set(value);
+            b.set(value * 10);
+        });
+    });
+
+    source.set(2);
+
+    assert_eq!(ops.borrow().as_slice(), &[(0, 0), (1, 10), (2, 20)]);
+}
+
+#[test]
+fn panicking_batch_is_ended() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+    let signal = create_signal(0);
+
+    let log_copy = log.clone();
+    create_effect(move |_| log_copy.borrow_mut().push(signal.get()));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        batch(|| {
+            signal.set(1);
+            panic!("failed");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(log.take(), [0]);
+
+    // the effect scheduled by the panicking batch runs with the next write
+    signal.set(2);
+    assert_eq!(log.take(), [2]);
+}
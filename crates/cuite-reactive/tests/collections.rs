@@ -0,0 +1,220 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use cuite_reactive::{
+    create_effect, create_root, create_signal_map, create_signal_vec, MapDiff, SignalVec, VecDiff,
+};
+
+use self::common::Rng;
+
+fn random_op(rng: &mut Rng, vec: SignalVec<i32>) {
+    let len = vec.get_untracked().len();
+    let value = rng.below(100) as i32;
+
+    match rng.below(10) {
+        0..=3 => vec.insert(rng.below(len + 1), value),
+        4 | 5 if len > 0 => drop(vec.remove(rng.below(len))),
+        6 | 7 if len > 0 => vec.set(rng.below(len), value),
+        8 if len > 0 => vec.move_item(rng.below(len), rng.below(len)),
+        9 if rng.below(10) == 0 => vec.clear(),
+        _ => vec.push(value),
+    }
+}
+
+#[test]
+fn diffs_are_recorded() {
+    // listeners are stored inside of nodes, which are `Send` with the `sync`
+    // feature
+    let diffs: Arc<Mutex<Vec<VecDiff<i32>>>> = Default::default();
+
+    let vec = create_signal_vec(vec![1, 2]);
+
+    let diffs_copy = diffs.clone();
+    vec.subscribe(move |diff| diffs_copy.lock().unwrap().push(diff.clone()));
+
+    vec.push(3);
+    vec.remove(0);
+    vec.move_item(1, 0);
+    vec.set(1, 10);
+    vec.clear();
+
+    assert_eq!(
+        diffs.lock().unwrap().as_slice(),
+        &[
+            VecDiff::Insert { index: 2, value: 3 },
+            VecDiff::Remove { index: 0 },
+            VecDiff::Move { from: 1, to: 0 },
+            VecDiff::Update {
+                index: 1,
+                value: 10
+            },
+            VecDiff::Clear,
+        ]
+    );
+}
+
+#[test]
+fn replayed_diffs_match() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+
+    let vec = create_signal_vec(vec![1, 2, 3]);
+
+    let replica = Arc::new(Mutex::new(vec.get_untracked()));
+    let replica_copy = replica.clone();
+    vec.subscribe(move |diff| diff.clone().apply(&mut replica_copy.lock().unwrap()));
+
+    for _ in 0..500 {
+        random_op(&mut rng, vec);
+        assert_eq!(*replica.lock().unwrap(), vec.get_untracked());
+    }
+}
+
+#[test]
+fn combinators_are_incremental() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+
+    let vec = create_signal_vec(vec![5, 3, 8, 1]);
+    let mapped = vec.map(|v| v * 2);
+    let filtered = vec.filter(|v| v % 2 == 0);
+    let sorted = vec.sort_by(|a, b| a.cmp(b));
+    let chained = vec
+        .filter(|v| v % 3 != 0)
+        .map(|v| v + 1)
+        .sort_by(|a, b| b.cmp(a));
+
+    for _ in 0..500 {
+        random_op(&mut rng, vec);
+
+        let items = vec.get_untracked();
+
+        let expected = items.iter().map(|v| v * 2).collect::<Vec<_>>();
+        assert_eq!(mapped.get_untracked(), expected);
+
+        let expected = items
+            .iter()
+            .copied()
+            .filter(|v| v % 2 == 0)
+            .collect::<Vec<_>>();
+        assert_eq!(filtered.get_untracked(), expected);
+
+        let mut expected = items.clone();
+        expected.sort();
+        assert_eq!(sorted.get_untracked(), expected);
+
+        let mut expected = items
+            .iter()
+            .filter(|v| *v % 3 != 0)
+            .map(|v| v + 1)
+            .collect::<Vec<_>>();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(chained.get_untracked(), expected);
+    }
+}
+
+#[test]
+fn derived_collections_notify_readers() {
+    let ops: Rc<RefCell<Vec<Vec<i32>>>> = Default::default();
+
+    let vec = create_signal_vec(vec![3, 1, 2]);
+    let sorted = vec.sort_by(|a, b| a.cmp(b));
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| ops_copy.borrow_mut().push(sorted.get()));
+
+    vec.push(0);
+    vec.set(0, 5);
+
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &[vec![1, 2, 3], vec![0, 1, 2, 3], vec![0, 1, 2, 5]]
+    );
+}
+
+#[test]
+fn listeners_are_removed_on_cleanup() {
+    let diffs: Arc<Mutex<Vec<VecDiff<i32>>>> = Default::default();
+
+    let vec = create_signal_vec(vec![]);
+
+    let scope = create_root(|scope| {
+        let diffs = diffs.clone();
+        vec.subscribe(move |diff| diffs.lock().unwrap().push(diff.clone()));
+        scope
+    });
+
+    vec.push(1);
+    scope.dispose();
+    vec.push(2);
+
+    assert_eq!(
+        diffs.lock().unwrap().as_slice(),
+        &[VecDiff::Insert { index: 0, value: 1 }]
+    );
+}
+
+#[test]
+fn map_diffs() {
+    let diffs: Arc<Mutex<Vec<MapDiff<&str, i32>>>> = Default::default();
+
+    let map = create_signal_map([("a", 1)]);
+
+    let diffs_copy = diffs.clone();
+    map.subscribe(move |diff| diffs_copy.lock().unwrap().push(diff.clone()));
+
+    assert_eq!(map.insert("b", 2), None);
+    assert_eq!(map.insert("a", 10), Some(1));
+    assert_eq!(map.remove(&"b"), Some(2));
+    assert_eq!(map.remove(&"b"), None);
+    map.clear();
+
+    assert_eq!(
+        diffs.lock().unwrap().as_slice(),
+        &[
+            MapDiff::Insert { key: "b", value: 2 },
+            MapDiff::Update {
+                key: "a",
+                value: 10
+            },
+            MapDiff::Remove { key: "b" },
+            MapDiff::Clear,
+        ]
+    );
+}
+
+#[test]
+fn map_combinators() {
+    let mut rng = Rng(0xdeadbeefcafebabe);
+
+    let map = create_signal_map([(1, 1), (2, 2)]);
+    let mapped = map.map(|k, v| k * 100 + v);
+    let filtered = map.filter(|_, v| v % 2 == 0);
+
+    for _ in 0..500 {
+        let key = rng.below(20) as i32;
+        match rng.below(10) {
+            0..=5 => drop(map.insert(key, rng.below(100) as i32)),
+            6..=8 => drop(map.remove(&key)),
+            _ => map.clear(),
+        }
+
+        map.with_untracked(|entries| {
+            mapped.with_untracked(|mapped| {
+                assert_eq!(mapped.len(), entries.len());
+                for (k, v) in entries {
+                    assert_eq!(mapped[k], k * 100 + v);
+                }
+            });
+
+            filtered.with_untracked(|filtered| {
+                let expected = entries.iter().filter(|(_, v)| *v % 2 == 0);
+                assert_eq!(filtered.len(), expected.clone().count());
+                for (k, v) in expected {
+                    assert_eq!(filtered[k], *v);
+                }
+            });
+        });
+    }
+}
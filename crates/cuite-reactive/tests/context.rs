@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{
+    create_effect, create_root, create_signal, expect_context, provide_context, use_context,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Theme(&'static str);
+
+#[test]
+fn nearest_provider_wins() {
+    let ops: Rc<RefCell<Vec<Option<Theme>>>> = Default::default();
+
+    create_root(|_| {
+        provide_context(Theme("light"));
+
+        let ops = ops.clone();
+        create_effect(move |_| {
+            ops.borrow_mut().push(use_context::<Theme>());
+
+            provide_context(Theme("dark"));
+
+            let ops = ops.clone();
+            create_effect(move |_| {
+                ops.borrow_mut().push(use_context::<Theme>());
+            });
+        });
+    });
+
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &[Some(Theme("light")), Some(Theme("dark"))]
+    );
+}
+
+#[test]
+fn context_is_scoped() {
+    create_root(|_| provide_context(Theme("light")));
+
+    create_root(|_| {
+        assert_eq!(use_context::<Theme>(), None);
+        assert_eq!(use_context::<i32>(), None);
+    });
+}
+
+#[test]
+fn context_is_reprovided_on_rerun() {
+    let ops: Rc<RefCell<Vec<Option<Theme>>>> = Default::default();
+
+    let dark = create_signal(true);
+
+    create_root(|_| {
+        provide_context(Theme("light"));
+
+        let ops = ops.clone();
+        create_effect(move |_| {
+            if dark.get() {
+                provide_context(Theme("dark"));
+            }
+
+            ops.borrow_mut().push(Some(expect_context::<Theme>()));
+        });
+    });
+
+    dark.set(false);
+
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &[Some(Theme("dark")), Some(Theme("light"))]
+    );
+}
+
+#[test]
+#[should_panic(expected = "is not provided")]
+fn expect_missing_context() {
+    create_root(|_| expect_context::<Theme>());
+}
@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_memo, create_signal, Runtime};
+
+#[test]
+fn writes_are_flushed() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let runtime = Runtime::new();
+    runtime.set_deferred(true);
+
+    let signal = runtime.enter(|| {
+        let signal = create_signal(0);
+
+        // the first run is still immediate
+        let log = log.clone();
+        create_effect(move |_| log.borrow_mut().push(signal.get()));
+
+        signal
+    });
+    assert_eq!(log.take(), [0]);
+    assert!(!runtime.is_dirty());
+
+    runtime.enter(|| {
+        signal.set(1);
+        signal.set(2);
+    });
+    assert!(log.borrow().is_empty());
+    assert!(runtime.is_dirty());
+
+    // the writes are coalesced into one run
+    runtime.flush();
+    assert_eq!(log.take(), [2]);
+    assert!(!runtime.is_dirty());
+
+    runtime.flush();
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn effects_scheduled_while_flushing() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let runtime = Runtime::new();
+    runtime.set_deferred(true);
+
+    let source = runtime.enter(|| {
+        let source = create_signal(0);
+        let target = create_signal(0);
+
+        create_effect(move |_| target.set(source.get() * 10));
+
+        let log = log.clone();
+        create_effect(move |_| log.borrow_mut().push(target.get()));
+
+        source
+    });
+    log.take();
+
+    runtime.enter(|| source.set(1));
+    runtime.flush();
+    assert_eq!(log.take(), [10]);
+    assert!(!runtime.is_dirty());
+}
+
+#[test]
+fn unchanged_memos_leave_the_runtime_clean() {
+    let runtime = Runtime::new();
+    runtime.set_deferred(true);
+
+    let signal = runtime.enter(|| {
+        let signal = create_signal(1);
+        let parity = create_memo(move |_| signal.get() % 2);
+        create_effect(move |_| {
+            parity.get();
+        });
+        signal
+    });
+
+    runtime.enter(|| signal.set(3));
+    assert!(runtime.is_dirty());
+
+    runtime.flush();
+    assert!(!runtime.is_dirty());
+}
+
+#[test]
+fn switching_back_runs_pending_effects() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let runtime = Runtime::new();
+    runtime.set_deferred(true);
+    assert!(runtime.is_deferred());
+
+    let signal = runtime.enter(|| {
+        let signal = create_signal(0);
+
+        let log = log.clone();
+        create_effect(move |_| log.borrow_mut().push(signal.get()));
+
+        signal
+    });
+    log.take();
+
+    runtime.enter(|| signal.set(1));
+    runtime.set_deferred(false);
+    assert_eq!(log.take(), [1]);
+
+    runtime.enter(|| signal.set(2));
+    assert_eq!(log.take(), [2]);
+}
@@ -0,0 +1,104 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_memo, create_signal};
+
+#[test]
+fn branch_switch_drops_stale_sources() {
+    let runs = Rc::new(Cell::new(0));
+
+    let cond = create_signal(true);
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    let runs_copy = runs.clone();
+    create_effect(move |_| {
+        runs_copy.set(runs_copy.get() + 1);
+        if cond.get() {
+            a.get();
+        } else {
+            b.get();
+        }
+    });
+
+    assert_eq!(runs.get(), 1);
+
+    b.set(1);
+    assert_eq!(runs.get(), 1);
+
+    a.set(1);
+    assert_eq!(runs.get(), 2);
+
+    cond.set(false);
+    assert_eq!(runs.get(), 3);
+
+    a.set(2);
+    assert_eq!(runs.get(), 3);
+
+    b.set(2);
+    assert_eq!(runs.get(), 4);
+
+    cond.set(true);
+    assert_eq!(runs.get(), 5);
+
+    b.set(3);
+    assert_eq!(runs.get(), 5);
+
+    a.set(3);
+    assert_eq!(runs.get(), 6);
+}
+
+#[test]
+fn early_return_unsubscribes() {
+    let ops: Rc<RefCell<Vec<Option<i32>>>> = Default::default();
+
+    let enabled = create_signal(true);
+    let value = create_signal(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        if !enabled.get() {
+            ops_copy.borrow_mut().push(None);
+            return;
+        }
+
+        ops_copy.borrow_mut().push(Some(value.get()));
+    });
+
+    value.set(1);
+    enabled.set(false);
+    value.set(2);
+    value.set(3);
+    enabled.set(true);
+
+    assert_eq!(ops.borrow().as_slice(), &[Some(0), Some(1), None, Some(3)]);
+}
+
+#[test]
+fn memo_branch_switch() {
+    let runs = Rc::new(Cell::new(0));
+
+    let cond = create_signal(true);
+    let a = create_signal(1);
+    let b = create_signal(2);
+
+    let runs_copy = runs.clone();
+    let memo = create_memo(move |_| {
+        runs_copy.set(runs_copy.get() + 1);
+        if cond.get() {
+            a.get()
+        } else {
+            b.get()
+        }
+    });
+
+    assert_eq!(memo.get(), 1);
+
+    cond.set(false);
+    assert_eq!(memo.get(), 2);
+    assert_eq!(runs.get(), 2);
+
+    a.set(10);
+    assert_eq!(memo.get(), 2);
+    assert_eq!(runs.get(), 2);
+}
@@ -0,0 +1,67 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use cuite_reactive::{create_memo, create_root, create_signal, ReactiveError, Signal};
+
+fn disposed_signal() -> Signal<i32> {
+    let (signal, scope) = create_root(|scope| (create_signal(1), scope));
+    scope.dispose();
+    signal
+}
+
+#[test]
+fn disposed_signal_errors() {
+    let signal = disposed_signal();
+
+    assert!(matches!(
+        signal.try_get(),
+        Err(ReactiveError::Disposed {
+            type_name: "i32",
+            ..
+        })
+    ));
+    assert!(matches!(
+        signal.try_set(2),
+        Err(ReactiveError::Disposed { .. })
+    ));
+    assert!(matches!(
+        signal.try_update(|v| *v += 1),
+        Err(ReactiveError::Disposed { .. })
+    ));
+}
+
+#[test]
+fn disposed_signal_panic_message() {
+    let signal = disposed_signal();
+
+    let err = catch_unwind(AssertUnwindSafe(|| signal.get())).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+
+    assert!(message.contains("NodeId("), "{message}");
+    assert!(message.contains("i32"), "{message}");
+    assert!(message.contains("disposed"), "{message}");
+}
+
+#[test]
+fn reentrant_borrow() {
+    let signal = create_signal(1);
+
+    let res = signal.with(|_| signal.try_set(2));
+    assert!(matches!(res, Err(ReactiveError::ReentrantBorrow { .. })));
+
+    let res = signal.update(|_| signal.try_get());
+    assert!(matches!(res, Err(ReactiveError::ReentrantBorrow { .. })));
+
+    assert_eq!(signal.get(), 1);
+}
+
+#[test]
+fn disposed_memo() {
+    let (memo, scope) = create_root(|scope| (create_memo(|_| 1), scope));
+    assert_eq!(memo.try_get(), Ok(1));
+
+    scope.dispose();
+    assert!(matches!(
+        memo.try_get(),
+        Err(ReactiveError::Disposed { .. })
+    ));
+}
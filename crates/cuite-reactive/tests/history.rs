@@ -0,0 +1,258 @@
+#![cfg(feature = "serde")]
+
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use cuite_reactive::{
+    batch, create_effect, create_memo, create_signal, HistoryEntry, HistoryError, Recorder,
+    Runtime, Signal,
+};
+use serde_json::json;
+
+#[test]
+fn snapshot_and_restore() {
+    let runs = Rc::new(RefCell::new(0));
+
+    let name = create_signal(String::from("a"));
+    let count = create_signal(1);
+    let unrecorded = create_signal(1);
+
+    let runs_copy = runs.clone();
+    create_effect(move |_| {
+        name.track();
+        count.track();
+        *runs_copy.borrow_mut() += 1;
+    });
+
+    let recorder = Recorder::new(16);
+    recorder.register("name", name);
+    recorder.register("count", count);
+
+    let snapshot = recorder.snapshot();
+    assert_eq!(snapshot.get("name"), Some(&json!("a")));
+    assert_eq!(snapshot.get("count"), Some(&json!(1)));
+    assert_eq!(snapshot.iter().count(), 2);
+
+    name.set(String::from("b"));
+    count.set(2);
+    unrecorded.set(2);
+    runs.take();
+
+    // the values are restored in a single batch
+    recorder.restore(&snapshot).unwrap();
+    assert_eq!(name.get(), "a");
+    assert_eq!(count.get(), 1);
+    assert_eq!(unrecorded.get(), 2);
+    assert_eq!(runs.take(), 1);
+
+    // snapshots can be saved
+    let saved = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&saved).unwrap(),
+        json!({ "values": { "count": 1, "name": "a" } })
+    );
+}
+
+#[test]
+fn stepping_through_history() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let count = create_signal(0);
+    let recorder = Recorder::new(16);
+    recorder.register("count", count);
+
+    let log_copy = log.clone();
+    create_effect(move |_| log_copy.borrow_mut().push(count.get()));
+
+    for i in 1..=3 {
+        count.set(i);
+    }
+    log.take();
+    assert_eq!(recorder.len(), 3);
+    assert_eq!(recorder.position(), 3);
+
+    assert!(recorder.step_back().unwrap());
+    assert!(recorder.step_back().unwrap());
+    assert_eq!(count.get(), 1);
+    assert_eq!(log.take(), [2, 1]);
+
+    // traveling isn't recorded
+    assert_eq!(recorder.len(), 3);
+    assert_eq!(recorder.position(), 1);
+
+    assert!(recorder.step_forward().unwrap());
+    assert_eq!(count.get(), 2);
+
+    recorder.go_to(0).unwrap();
+    assert_eq!(count.get(), 0);
+    assert!(!recorder.step_back().unwrap());
+
+    recorder.go_to(3).unwrap();
+    assert_eq!(count.get(), 3);
+    assert!(!recorder.step_forward().unwrap());
+
+    // a write after stepping back discards the undone writes
+    recorder.go_to(1).unwrap();
+    count.set(10);
+    let values = recorder.history().into_iter().map(|e| e.value);
+    assert_eq!(values.collect::<Vec<_>>(), [json!(1), json!(10)]);
+    assert_eq!(recorder.position(), 2);
+}
+
+#[test]
+fn history_is_bounded() {
+    let count = create_signal(0);
+    let recorder = Recorder::new(3);
+    recorder.register("count", count);
+
+    batch(|| {
+        for i in 1..=5 {
+            count.set(i);
+        }
+    });
+    assert_eq!(recorder.len(), 3);
+
+    // the dropped writes are still applied when going back to the start
+    recorder.go_to(0).unwrap();
+    assert_eq!(count.get(), 2);
+}
+
+/// Creates the registered signals of a session, which are read by a memo.
+fn session(recorder: &Recorder) -> (Signal<Vec<u32>>, Signal<bool>) {
+    let items = create_signal(Vec::new());
+    let enabled = create_signal(true);
+    recorder.register("items", items);
+    recorder.register("enabled", enabled);
+
+    let total = create_memo(move |_| match enabled.get() {
+        true => items.with(|items| items.iter().sum()),
+        false => 0,
+    });
+    create_effect(move |_| {
+        total.get();
+    });
+
+    (items, enabled)
+}
+
+#[test]
+fn replay_is_deterministic() {
+    let recorded = Runtime::new();
+    let (history, snapshot) = recorded.enter(|| {
+        let recorder = Recorder::new(64);
+        let (items, enabled) = session(&recorder);
+
+        items.update(|items| items.push(1));
+        items.update(|items| items.push(2));
+        enabled.set(false);
+        items.update(|items| items.retain(|&item| item != 1));
+        enabled.set(true);
+
+        (recorder.history(), recorder.snapshot())
+    });
+    assert_eq!(history.len(), 5);
+
+    // histories can be saved and loaded in another run
+    let saved = serde_json::to_string(&history).unwrap();
+    let loaded = serde_json::from_str::<Vec<HistoryEntry>>(&saved).unwrap();
+
+    let replayed = Runtime::new();
+    replayed.enter(|| {
+        let recorder = Recorder::new(64);
+        let (items, enabled) = session(&recorder);
+
+        recorder.replay(&loaded).unwrap();
+        assert_eq!(recorder.history(), history);
+        assert_eq!(recorder.snapshot(), snapshot);
+        assert_eq!(items.get(), [2]);
+        assert!(enabled.get());
+    });
+}
+
+#[test]
+fn invalid_entries() {
+    let count = create_signal(0);
+    let recorder = Recorder::new(16);
+    recorder.register("count", count);
+
+    let unknown = HistoryEntry {
+        signal: String::from("missing"),
+        value: json!(1),
+    };
+    let err = recorder.replay(&[unknown]).unwrap_err();
+    assert!(matches!(err, HistoryError::UnknownSignal { name } if name == "missing"));
+
+    let mismatched = HistoryEntry {
+        signal: String::from("count"),
+        value: json!("one"),
+    };
+    let err = recorder.replay(&[mismatched]).unwrap_err();
+    assert!(matches!(err, HistoryError::Deserialize { .. }));
+    assert_eq!(count.get(), 0);
+    assert!(recorder.is_empty());
+}
+
+#[test]
+fn replayed_writes_can_register_signals() {
+    let count = create_signal(0);
+    let doubled = create_signal(0);
+    let recorder = Recorder::new(16);
+    recorder.register("count", count);
+
+    let copy = recorder.clone();
+    create_effect(move |_| {
+        if count.get() > 0 {
+            copy.register("doubled", doubled);
+        }
+    });
+
+    let entry = HistoryEntry {
+        signal: String::from("count"),
+        value: json!(1),
+    };
+    recorder.replay(&[entry]).unwrap();
+
+    doubled.set(2);
+    assert_eq!(recorder.len(), 2);
+}
+
+#[test]
+fn recording_resumes_after_a_panicking_step() {
+    let count = create_signal(0);
+    let recorder = Recorder::new(16);
+    recorder.register("count", count);
+
+    let armed = Rc::new(Cell::new(false));
+    let armed_copy = armed.clone();
+    create_effect(move |_| {
+        if count.get() == 0 && armed_copy.get() {
+            panic!("failed");
+        }
+    });
+
+    count.set(1);
+    armed.set(true);
+    let result = catch_unwind(AssertUnwindSafe(|| recorder.go_to(0)));
+    assert!(result.is_err());
+
+    armed.set(false);
+    count.set(2);
+    assert_eq!(recorder.len(), 2);
+}
+
+#[test]
+fn dropping_the_recorder_stops_recording() {
+    let count = create_signal(0);
+    let recorder = Recorder::new(16);
+    recorder.register("count", count);
+
+    let copy = recorder.clone();
+    count.set(1);
+    drop(recorder);
+    count.set(2);
+    assert_eq!(copy.len(), 2);
+
+    drop(copy);
+    count.set(3);
+}
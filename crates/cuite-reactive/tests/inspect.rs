@@ -0,0 +1,163 @@
+use cuite_reactive::{
+    batch, create_effect, create_memo, create_root, create_signal, NodeType, Runtime, Trigger,
+};
+
+#[test]
+fn nodes_and_relationships() {
+    let runtime = Runtime::new();
+
+    let (scope, signal, memo, effect, line) = runtime.enter(|| {
+        create_root(|scope| {
+            let line = line!() + 1;
+            let signal = create_signal(1);
+            let memo = create_memo(move |_| signal.get() * 2);
+            let effect = create_effect(move |_| {
+                memo.get();
+            });
+
+            (scope, signal, memo, effect, line)
+        })
+    });
+
+    let nodes = runtime.nodes();
+    assert_eq!(nodes.len(), 4);
+
+    let scope = runtime.node(scope.id()).unwrap();
+    assert_eq!(scope.kind, NodeType::Scope);
+    assert_eq!(scope.parent, None);
+    assert_eq!(scope.children.len(), 3);
+
+    let signal = runtime.node(signal.id()).unwrap();
+    assert_eq!(signal.kind, NodeType::Signal);
+    assert_eq!(signal.parent, Some(scope.id));
+    assert_eq!(signal.subscribers, [memo.id()]);
+
+    let memo = runtime.node(memo.id()).unwrap();
+    assert_eq!(memo.kind, NodeType::Memo);
+    assert_eq!(memo.sources, [signal.id]);
+    assert_eq!(memo.subscribers, [effect.id()]);
+
+    let effect = runtime.node(effect.id()).unwrap();
+    assert_eq!(effect.kind, NodeType::Effect);
+    assert_eq!(effect.sources, [memo.id]);
+    assert!(effect.subscribers.is_empty());
+
+    let location = signal.location.unwrap();
+    assert!(location.file().ends_with("inspect.rs"));
+    assert_eq!(location.line(), line);
+    assert_eq!(memo.location.unwrap().line(), line + 1);
+    assert_eq!(effect.location.unwrap().line(), line + 2);
+}
+
+#[test]
+fn foreign_nodes_are_not_found() {
+    let runtime = Runtime::new();
+    let signal = create_signal(0);
+
+    assert!(runtime.node(signal.id()).is_none());
+    assert!(runtime.nodes().is_empty());
+}
+
+#[test]
+fn dot() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        create_root(|_| {
+            let signal = create_signal(0);
+            create_effect(move |_| signal.track());
+        })
+    });
+
+    let dot = runtime.to_dot();
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.ends_with("}\n"));
+
+    // the scope, the signal and the effect
+    assert_eq!(dot.matches("shape=").count(), 3);
+    // the dependency, and two ownership edges
+    assert_eq!(dot.matches(" -> ").count(), 3);
+    assert_eq!(dot.matches("style=dashed").count(), 2);
+    assert!(dot.contains("inspect.rs:"));
+}
+
+#[test]
+fn stats() {
+    let runtime = Runtime::new();
+    let signal = runtime.enter(|| {
+        let signal = create_signal(1);
+        let memo = create_memo(move |_| signal.get() + 1);
+        create_effect(move |_| {
+            signal.track();
+            memo.get();
+        });
+        signal
+    });
+
+    let stats = runtime.stats();
+    assert_eq!(stats.signals, 1);
+    assert_eq!(stats.memos, 1);
+    assert_eq!(stats.effects, 1);
+    assert_eq!(stats.edges, 3);
+    assert_eq!(stats.pending_effects, 0);
+
+    runtime.enter(|| {
+        batch(|| {
+            signal.set(2);
+            assert_eq!(runtime.stats().pending_effects, 1);
+        })
+    });
+    assert_eq!(runtime.stats().pending_effects, 0);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn leaks_are_reported() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let leaks = Rc::new(RefCell::new(Vec::new()));
+
+    let runtime = Runtime::new();
+    let leaks_copy = leaks.clone();
+    runtime.set_leak_handler(move |nodes| leaks_copy.borrow_mut().extend_from_slice(nodes));
+
+    let (outer, line) = runtime.enter(|| {
+        let line = line!() + 1;
+        create_signal(0);
+
+        let outer = create_root(|outer| {
+            // owned nodes are disposed together with the root, and nested
+            // roots are independent
+            create_signal(0);
+            create_root(|_| create_signal(0));
+            outer
+        });
+
+        (outer, line)
+    });
+
+    runtime.enter(|| outer.dispose());
+    assert_eq!(leaks.borrow().len(), 1);
+    assert_eq!(leaks.borrow()[0].kind, NodeType::Signal);
+    assert_eq!(leaks.borrow()[0].location.unwrap().line(), line);
+
+    // the leak is only reported once
+    runtime.enter(|| create_root(|scope| scope).dispose());
+    assert_eq!(leaks.borrow().len(), 1);
+    assert_eq!(runtime.stats().signals, 2);
+}
+
+#[test]
+fn default_trigger_location() {
+    let runtime = Runtime::new();
+
+    let (trigger, line) = runtime.enter(|| (Trigger::default(), line!()));
+
+    let node = runtime.node(trigger.id()).unwrap();
+    assert_eq!(node.kind, NodeType::Trigger);
+    assert_eq!(node.location.unwrap().line(), line);
+    assert_eq!(
+        format!("{trigger:?}"),
+        format!("Trigger {{ id: {:?} }}", trigger.id())
+    );
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use cuite_reactive::{
+    create_effect, create_keyed, create_root, create_signal, diff_keyed, on_cleanup, KeyedOp,
+    VecDiff,
+};
+
+use self::common::Rng;
+
+impl Rng {
+    /// Returns a shuffled list of unique keys below `max`.
+    fn keys(&mut self, max: u32) -> Vec<u32> {
+        let mut keys = (0..max).filter(|_| self.below(3) != 0).collect::<Vec<_>>();
+        for i in (1..keys.len()).rev() {
+            keys.swap(i, self.below(i + 1));
+        }
+        keys
+    }
+}
+
+fn apply(old: &[u32], new: &[u32], ops: &[KeyedOp]) -> Vec<u32> {
+    let mut list = old.to_vec();
+    for op in ops {
+        match *op {
+            KeyedOp::Remove { index } => {
+                list.remove(index);
+            }
+            KeyedOp::Insert { index, new_index } => list.insert(index, new[new_index]),
+            KeyedOp::Move { from, to } => {
+                let key = list.remove(from);
+                list.insert(to, key);
+            }
+        }
+    }
+    list
+}
+
+/// Length of the longest increasing subsequence, computed naively.
+fn lis_len(values: &[usize]) -> usize {
+    let mut lengths = vec![1; values.len()];
+    for i in 0..values.len() {
+        for j in 0..i {
+            if values[j] < values[i] {
+                lengths[i] = lengths[i].max(lengths[j] + 1);
+            }
+        }
+    }
+    lengths.into_iter().max().unwrap_or(0)
+}
+
+#[test]
+fn diff_simple() {
+    assert_eq!(
+        diff_keyed(&[1, 2, 3], &[1, 3]),
+        &[KeyedOp::Remove { index: 1 }]
+    );
+    assert_eq!(
+        diff_keyed(&[1, 3], &[1, 2, 3]),
+        &[KeyedOp::Insert {
+            index: 1,
+            new_index: 1
+        }]
+    );
+    assert_eq!(
+        diff_keyed(&[1, 2, 3, 4], &[4, 1, 2, 3]),
+        &[KeyedOp::Move { from: 3, to: 0 }]
+    );
+    assert_eq!(diff_keyed(&[1, 2, 3], &[1, 2, 3]), &[]);
+}
+
+#[test]
+fn diff_random() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+
+    for round in 0..550 {
+        // a few longer lists too
+        let max = if round < 500 { 20 } else { 300 };
+        let old = rng.keys(max);
+        let new = rng.keys(max);
+        let ops = diff_keyed(&old, &new);
+
+        assert_eq!(apply(&old, &new, &ops), new);
+
+        // retained keys, as positions in the new list
+        let retained = old
+            .iter()
+            .filter_map(|k| new.iter().position(|v| v == k))
+            .collect::<Vec<_>>();
+
+        let moves = ops
+            .iter()
+            .filter(|op| matches!(op, KeyedOp::Move { .. }))
+            .count();
+        assert_eq!(moves, retained.len() - lis_len(&retained));
+    }
+}
+
+#[test]
+fn diff_duplicates() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+
+    let old = [1, 1, 2];
+    let new = [2, 1, 1, 3];
+    assert_eq!(apply(&old, &new, &diff_keyed(&old, &new)), new);
+    assert_eq!(apply(&new, &old, &diff_keyed(&new, &old)), old);
+
+    // the k-th occurrence is matched with the k-th one
+    assert_eq!(diff_keyed(&new, &new), []);
+    assert_eq!(
+        diff_keyed(&[1, 2, 1], &[1, 1]),
+        [KeyedOp::Remove { index: 1 }]
+    );
+
+    for _ in 0..500 {
+        let old = (0..rng.below(12))
+            .map(|_| rng.below(5) as u32)
+            .collect::<Vec<_>>();
+        let new = (0..rng.below(12))
+            .map(|_| rng.below(5) as u32)
+            .collect::<Vec<_>>();
+        assert_eq!(apply(&old, &new, &diff_keyed(&old, &new)), new);
+        assert_eq!(diff_keyed(&old, &old), []);
+    }
+}
+
+#[test]
+fn diff_large() {
+    let old = (0..20_000).collect::<Vec<u32>>();
+    let new = old.iter().rev().copied().collect::<Vec<_>>();
+    let ops = diff_keyed(&old, &new);
+
+    // every item but the last one is moved after it
+    assert_eq!(ops.len(), old.len() - 1);
+    assert_eq!(
+        ops[0],
+        KeyedOp::Move {
+            from: 0,
+            to: 19_999
+        }
+    );
+    assert_eq!(ops[19_998], KeyedOp::Move { from: 0, to: 1 });
+}
+
+#[test]
+fn rows_are_created_and_disposed_by_key() {
+    let log: Rc<RefCell<Vec<String>>> = Default::default();
+    let diffs: Arc<Mutex<Vec<VecDiff<String>>>> = Default::default();
+
+    let items = create_signal(vec![1, 2, 3]);
+
+    let (rows, scope) = create_root(|scope| {
+        let log = log.clone();
+        let rows = create_keyed(
+            move || items.get(),
+            |item| *item,
+            move |item| {
+                log.borrow_mut().push(format!("create {item}"));
+
+                let log = log.clone();
+                on_cleanup(move || log.borrow_mut().push(format!("dispose {item}")));
+
+                format!("row {item}")
+            },
+        );
+
+        let diffs = diffs.clone();
+        rows.subscribe(move |diff| diffs.lock().unwrap().push(diff.clone()));
+
+        (rows, scope)
+    });
+
+    assert_eq!(rows.get(), ["row 1", "row 2", "row 3"]);
+
+    log.borrow_mut().clear();
+    items.set(vec![3, 1, 2]);
+    assert_eq!(rows.get(), ["row 3", "row 1", "row 2"]);
+    assert!(log.borrow().is_empty());
+    assert_eq!(
+        diffs.lock().unwrap().as_slice(),
+        &[VecDiff::Move { from: 2, to: 0 }]
+    );
+
+    items.set(vec![3, 4, 2]);
+    assert_eq!(rows.get(), ["row 3", "row 4", "row 2"]);
+    assert_eq!(log.borrow().as_slice(), &["dispose 1", "create 4"]);
+
+    log.borrow_mut().clear();
+    scope.dispose();
+    log.borrow_mut().sort();
+    assert_eq!(
+        log.borrow().as_slice(),
+        &["dispose 2", "dispose 3", "dispose 4"]
+    );
+}
+
+#[test]
+fn rows_own_their_nodes() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let items = create_signal(vec![1, 2]);
+    let multiplier = create_signal(1);
+
+    let log_copy = log.clone();
+    create_keyed(
+        move || items.get(),
+        |item| *item,
+        move |item| {
+            let log = log_copy.clone();
+            create_effect(move |_| log.borrow_mut().push(item * multiplier.get()));
+        },
+    );
+
+    items.set(vec![2]);
+    log.borrow_mut().clear();
+
+    multiplier.set(10);
+    assert_eq!(log.borrow().as_slice(), &[20]);
+}
+
+#[test]
+fn rows_with_duplicate_keys() {
+    let created = Rc::new(RefCell::new(Vec::new()));
+    let items = create_signal(vec![1, 1, 2]);
+
+    let created_copy = created.clone();
+    let rows = create_keyed(
+        move || items.get(),
+        |item| *item,
+        move |item| {
+            created_copy.borrow_mut().push(item);
+            item
+        },
+    );
+    assert_eq!(rows.get(), [1, 1, 2]);
+
+    // setting the same list again keeps every row
+    created.borrow_mut().clear();
+    items.set(vec![1, 1, 2]);
+    assert!(created.borrow().is_empty());
+
+    items.set(vec![2, 1, 1, 3]);
+    assert_eq!(rows.get(), [2, 1, 1, 3]);
+    assert_eq!(created.borrow().as_slice(), &[3]);
+
+    items.set(vec![1]);
+    assert_eq!(rows.get(), [1]);
+}
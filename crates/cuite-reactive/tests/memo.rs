@@ -0,0 +1,69 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_memo, create_signal};
+
+#[test]
+fn memo_is_lazy() {
+    let runs = Rc::new(Cell::new(0));
+
+    let signal = create_signal(1);
+
+    let runs_copy = runs.clone();
+    let memo = create_memo(move |_| {
+        runs_copy.set(runs_copy.get() + 1);
+        signal.get() * 2
+    });
+
+    assert_eq!(runs.get(), 0);
+    assert_eq!(memo.get(), 2);
+    assert_eq!(memo.get(), 2);
+    assert_eq!(runs.get(), 1);
+
+    signal.set(2);
+    assert_eq!(runs.get(), 1);
+    assert_eq!(memo.get(), 4);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn memo_skips_unchanged_values() {
+    let ops: Rc<RefCell<Vec<bool>>> = Default::default();
+
+    let signal = create_signal(1);
+    let is_even = create_memo(move |_| signal.get() % 2 == 0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push(is_even.get());
+    });
+
+    signal.set(3);
+    signal.set(5);
+    signal.set(6);
+    signal.set(8);
+    signal.set(9);
+
+    assert_eq!(ops.borrow().as_slice(), &[false, true, false]);
+}
+
+#[test]
+fn memo_diamond_is_glitch_free() {
+    let ops: Rc<RefCell<Vec<(i32, i32, i32)>>> = Default::default();
+
+    let signal = create_signal(1);
+    let double = create_memo(move |_| signal.get() * 2);
+    let triple = create_memo(move |_| signal.get() * 3);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy
+            .borrow_mut()
+            .push((signal.get(), double.get(), triple.get()));
+    });
+
+    signal.set(2);
+    signal.set(3);
+
+    assert_eq!(ops.borrow().as_slice(), &[(1, 2, 3), (2, 4, 6), (3, 6, 9)]);
+}
@@ -0,0 +1,141 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use cuite_reactive::{
+    create_effect, create_memo_with_options, create_signal, create_signal_with_options,
+    create_trigger, SignalOptions, SignalSet,
+};
+
+fn count_runs(func: impl Fn() + 'static) -> Rc<Cell<usize>> {
+    let runs = Rc::new(Cell::new(0));
+
+    let runs_copy = runs.clone();
+    create_effect(move |_| {
+        func();
+        runs_copy.set(runs_copy.get() + 1);
+    });
+
+    runs
+}
+
+#[test]
+fn signals_always_notify_by_default() {
+    let signal = create_signal(1);
+    let runs = count_runs(move || signal.track());
+
+    signal.set(1);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn dedup() {
+    let signal = create_signal_with_options(1, SignalOptions::dedup());
+    let runs = count_runs(move || signal.track());
+
+    assert_eq!(signal.set(1), 1);
+    assert_eq!(runs.get(), 1);
+
+    signal.set(2);
+    assert_eq!(runs.get(), 2);
+
+    // in-place updates can't be compared
+    signal.update(|_| {});
+    assert_eq!(runs.get(), 3);
+}
+
+#[test]
+fn dedup_through_traits() {
+    fn set(signal: &impl SignalSet<Value = i32>, value: i32) {
+        signal.set(value);
+    }
+
+    let signal = create_signal_with_options(1, SignalOptions::dedup());
+    let runs = count_runs(move || signal.track());
+
+    set(&signal, 1);
+    set(&signal.split().1, 1);
+    assert_eq!(runs.get(), 1);
+
+    set(&signal, 2);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn custom_equality() {
+    let signal = create_signal_with_options(
+        String::from("Hello"),
+        SignalOptions::equality(|a: &String, b: &String| a.eq_ignore_ascii_case(b)),
+    );
+    let runs = count_runs(move || signal.track());
+
+    signal.set("HELLO".into());
+    assert_eq!(runs.get(), 1);
+    assert_eq!(signal.get_untracked(), "HELLO");
+
+    signal.set("World".into());
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn notify() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let signal = create_signal(vec![1]);
+
+    let log_copy = log.clone();
+    create_effect(move |_| log_copy.borrow_mut().push(signal.with(|v| v.len() as i32)));
+
+    signal.update_untracked(|v| v.push(2));
+    assert_eq!(log.borrow().as_slice(), &[1]);
+
+    signal.notify();
+    assert_eq!(log.borrow().as_slice(), &[1, 2]);
+}
+
+#[test]
+fn memo_always_notify() {
+    let signal = create_signal(1);
+    let memo = create_memo_with_options(move |_| signal.get() % 2, SignalOptions::always_notify());
+    let runs = count_runs(move || memo.with(|_| ()));
+
+    signal.set(3);
+    assert_eq!(memo.get(), 1);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn memo_custom_equality() {
+    // values without `PartialEq` can still be deduplicated by a key
+    struct Value(i32);
+
+    let signal = create_signal(1);
+    let memo = create_memo_with_options(
+        move |_| Value(signal.get() / 10),
+        SignalOptions::equality(|a: &Value, b: &Value| a.0 == b.0),
+    );
+    let runs = count_runs(move || memo.with(|_| ()));
+
+    signal.set(5);
+    assert_eq!(runs.get(), 1);
+
+    signal.set(15);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn trigger() {
+    let external = Rc::new(RefCell::new(Vec::new()));
+    let trigger = create_trigger();
+
+    let external_copy = external.clone();
+    let runs = count_runs(move || {
+        trigger.track();
+        let _ = external_copy.borrow().len();
+    });
+
+    external.borrow_mut().push(1);
+    assert_eq!(runs.get(), 1);
+
+    trigger.notify();
+    assert_eq!(runs.get(), 2);
+}
@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{
+    batch, create_effect_with_phase, create_idle_effect, create_layout_effect,
+    create_pre_layout_effect, create_render_effect, create_signal, EffectPhase,
+};
+
+type Log = Rc<RefCell<Vec<&'static str>>>;
+
+#[test]
+fn phases_are_run_in_order() {
+    let log = Log::default();
+    let signal = create_signal(0);
+
+    for (phase, name) in [
+        (EffectPhase::Idle, "idle"),
+        (EffectPhase::Render, "render"),
+        (EffectPhase::Layout, "layout"),
+        (EffectPhase::PreLayout, "pre-layout"),
+    ] {
+        let log = log.clone();
+        create_effect_with_phase(phase, move |_| {
+            signal.track();
+            log.borrow_mut().push(name);
+        });
+    }
+
+    // the first run is immediate
+    assert_eq!(
+        log.take().as_slice(),
+        &["idle", "render", "layout", "pre-layout"]
+    );
+
+    signal.set(1);
+    assert_eq!(
+        log.take().as_slice(),
+        &["pre-layout", "layout", "render", "idle"]
+    );
+}
+
+#[test]
+fn earlier_phases_run_first_when_scheduled_by_effects() {
+    let log = Log::default();
+    let content = create_signal(0);
+    let size = create_signal(0);
+
+    let log_copy = log.clone();
+    create_idle_effect(move |_| {
+        content.track();
+        log_copy.borrow_mut().push("idle");
+    });
+
+    let log_copy = log.clone();
+    create_render_effect(move |_| {
+        size.set(content.get() * 2);
+        log_copy.borrow_mut().push("render");
+    });
+
+    let log_copy = log.clone();
+    create_layout_effect(move |_| {
+        size.track();
+        log_copy.borrow_mut().push("layout");
+    });
+
+    log.take();
+    content.set(1);
+
+    // the layout effect scheduled by the render effect preempts the idle one
+    assert_eq!(log.take().as_slice(), &["render", "layout", "idle"]);
+}
+
+#[test]
+fn phases_in_a_batch() {
+    let log = Log::default();
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    let log_copy = log.clone();
+    create_render_effect(move |_| {
+        a.track();
+        log_copy.borrow_mut().push("render");
+    });
+
+    let log_copy = log.clone();
+    create_pre_layout_effect(move |_| {
+        b.track();
+        log_copy.borrow_mut().push("pre-layout");
+    });
+
+    log.take();
+    batch(|| {
+        a.set(1);
+        b.set(1);
+    });
+
+    assert_eq!(log.take().as_slice(), &["pre-layout", "render"]);
+}
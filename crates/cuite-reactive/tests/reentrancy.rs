@@ -0,0 +1,205 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use cuite_reactive::{batch, create_effect, create_signal, set_max_effect_iterations};
+
+#[test]
+fn chain_of_effects() {
+    let ops: Rc<RefCell<Vec<(i32, i32, i32)>>> = Default::default();
+
+    let a = create_signal(0);
+    let b = create_signal(0);
+    let c = create_signal(0);
+
+    create_effect(move |_| {
+        b.set(a.get() + 1);
+    });
+
+    create_effect(move |_| {
+        c.set(b.get() + 1);
+    });
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push((a.get(), b.get(), c.get()));
+    });
+
+    ops.borrow_mut().clear();
+    a.set(10);
+
+    assert_eq!(ops.borrow().last(), Some(&(10, 11, 12)));
+    assert!(ops.borrow().iter().all(|&(_, b, c)| c <= b + 1));
+}
+
+#[test]
+fn writes_are_queued_until_effect_completes() {
+    let ops: Rc<RefCell<Vec<&str>>> = Default::default();
+
+    let trigger = create_signal(0);
+    let target = create_signal(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        target.get();
+        ops_copy.borrow_mut().push("reader");
+    });
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        let value = trigger.get();
+        ops_copy.borrow_mut().push("writer start");
+        target.set(value);
+        target.set(value + 1);
+        ops_copy.borrow_mut().push("writer end");
+    });
+
+    ops.borrow_mut().clear();
+    trigger.set(1);
+
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &["writer start", "writer end", "reader"]
+    );
+}
+
+#[test]
+fn converging_loop() {
+    let counter = create_signal(0);
+    let other = create_signal(0);
+
+    create_effect(move |_| {
+        let value = counter.get();
+        if value < 50 {
+            other.set(value + 1);
+        }
+    });
+
+    create_effect(move |_| {
+        counter.set(other.get());
+    });
+
+    assert_eq!(counter.get(), 50);
+}
+
+#[test]
+#[should_panic(expected = "infinite loop")]
+fn infinite_loop_detection() {
+    set_max_effect_iterations(10);
+
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    create_effect(move |_| {
+        b.set(a.get() + 1);
+    });
+
+    create_effect(move |_| {
+        a.set(b.get() + 1);
+    });
+}
+
+#[test]
+fn effects_run_after_a_panicking_effect() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+    let signal = create_signal(0);
+
+    let log_copy = log.clone();
+    create_effect(move |_| {
+        let value = signal.get();
+        if value == 1 {
+            panic!("failed");
+        }
+        log_copy.borrow_mut().push(value);
+    });
+
+    let result = catch_unwind(AssertUnwindSafe(|| signal.set(1)));
+    assert!(result.is_err());
+
+    signal.set(2);
+    assert_eq!(log.take(), [0, 2]);
+}
+
+#[test]
+fn effects_run_after_an_infinite_loop_is_detected() {
+    set_max_effect_iterations(10);
+
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+    let looping = Rc::new(Cell::new(false));
+
+    let a = create_signal(0);
+    let b = create_signal(0);
+
+    let (looping_copy, log_copy) = (looping.clone(), log.clone());
+    create_effect(move |_| {
+        let value = a.get();
+        log_copy.borrow_mut().push(value);
+        if looping_copy.get() {
+            b.set(value + 1);
+        }
+    });
+
+    let (looping_copy, log_copy) = (looping.clone(), log.clone());
+    create_effect(move |_| {
+        let value = b.get();
+        log_copy.borrow_mut().push(value);
+        if looping_copy.get() {
+            a.set(value + 1);
+        }
+    });
+
+    looping.set(true);
+    let result = catch_unwind(AssertUnwindSafe(|| a.set(1)));
+    assert!(result.is_err());
+
+    looping.set(false);
+    log.take();
+
+    a.set(100);
+    b.set(200);
+    assert_eq!(log.take(), [100, 200]);
+}
+
+#[test]
+fn effect_writing_a_signal_it_has_read() {
+    let log: Rc<RefCell<Vec<i32>>> = Default::default();
+    let signal = create_signal(0);
+
+    let log_copy = log.clone();
+    create_effect(move |_| {
+        let value = signal.get();
+        log_copy.borrow_mut().push(value);
+        if value == 0 {
+            signal.set(1);
+        }
+    });
+
+    assert_eq!(log.take(), [0, 1]);
+
+    signal.set(5);
+    signal.set(6);
+    assert_eq!(log.take(), [5, 6]);
+}
+
+#[test]
+fn effects_are_queued_once() {
+    let runs = Rc::new(Cell::new(0));
+    let signals = (0..150).map(create_signal).collect::<Vec<_>>();
+
+    let (runs_copy, signals_copy) = (runs.clone(), signals.clone());
+    create_effect(move |_| {
+        signals_copy.iter().for_each(|signal| signal.track());
+        runs_copy.set(runs_copy.get() + 1);
+    });
+
+    batch(|| {
+        let first = signals[0];
+        first.set(-1);
+        create_effect(move |_| first.track());
+        for signal in &signals[1..] {
+            signal.set(-1);
+        }
+    });
+
+    assert_eq!(runs.get(), 2);
+}
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use cuite_reactive::{
+    create_effect, create_resource, create_root, create_signal, set_executor, Runtime, TestExecutor,
+};
+
+/// Future which is resolved manually from the test.
+struct Deferred<T> {
+    state: Rc<RefCell<(Option<T>, Option<Waker>)>>,
+}
+
+impl<T> Future for Deferred<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+        match state.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+type Requests<T> = Rc<RefCell<Vec<Rc<RefCell<(Option<T>, Option<Waker>)>>>>>;
+
+fn resolve<T>(requests: &Requests<T>, index: usize, value: T) {
+    let request = requests.borrow()[index].clone();
+    let mut state = request.borrow_mut();
+    state.0 = Some(value);
+    if let Some(waker) = state.1.take() {
+        waker.wake();
+    }
+}
+
+#[test]
+fn resource_loads() {
+    let executor = TestExecutor::new();
+    set_executor(executor.clone());
+
+    let id = create_signal(1);
+    let resource = create_resource(
+        move || id.get(),
+        |id| async move { Ok::<_, String>(id * 10) },
+    );
+
+    assert!(resource.loading());
+    assert_eq!(resource.get(), None);
+
+    executor.run_until_stalled();
+    assert!(!resource.loading());
+    assert_eq!(resource.get(), Some(10));
+
+    id.set(2);
+    assert!(resource.loading());
+    assert_eq!(resource.get(), Some(10));
+
+    executor.run_until_stalled();
+    assert_eq!(resource.get(), Some(20));
+    assert_eq!(executor.pending_tasks(), 0);
+}
+
+#[test]
+fn resource_error() {
+    let executor = TestExecutor::new();
+    set_executor(executor.clone());
+
+    let id = create_signal(1);
+    let resource = create_resource(
+        move || id.get(),
+        |id| async move {
+            if id < 0 {
+                Err(format!("invalid id {id}"))
+            } else {
+                Ok(id)
+            }
+        },
+    );
+
+    executor.run_until_stalled();
+    assert!(resource.error().is_none());
+
+    id.set(-1);
+    executor.run_until_stalled();
+    assert_eq!(resource.error().unwrap().to_string(), "invalid id -1");
+    assert_eq!(resource.get(), Some(1));
+
+    id.set(2);
+    executor.run_until_stalled();
+    assert!(resource.error().is_none());
+    assert_eq!(resource.get(), Some(2));
+}
+
+#[test]
+fn stale_futures_are_cancelled() {
+    let executor = TestExecutor::new();
+    set_executor(executor.clone());
+
+    let requests: Requests<i32> = Default::default();
+    let ops: Rc<RefCell<Vec<Option<i32>>>> = Default::default();
+
+    let id = create_signal(1);
+
+    let requests_copy = requests.clone();
+    let resource = create_resource(
+        move || id.get(),
+        move |_| {
+            let state = Rc::new(RefCell::new((None, None)));
+            requests_copy.borrow_mut().push(state.clone());
+            async move { Ok::<_, String>(Deferred { state }.await) }
+        },
+    );
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| ops_copy.borrow_mut().push(resource.get()));
+
+    executor.run_until_stalled();
+    id.set(2);
+    executor.run_until_stalled();
+    assert_eq!(executor.pending_tasks(), 1);
+
+    // the first request is stale, so its result is ignored
+    resolve(&requests, 0, 100);
+    executor.run_until_stalled();
+    assert!(resource.loading());
+
+    resolve(&requests, 1, 200);
+    executor.run_until_stalled();
+    assert!(!resource.loading());
+
+    assert_eq!(ops.borrow().as_slice(), &[None, Some(200)]);
+}
+
+#[test]
+fn dispose_cancels_fetch() {
+    let executor = TestExecutor::new();
+    set_executor(executor.clone());
+
+    let requests: Requests<i32> = Default::default();
+
+    let scope = create_root(|scope| {
+        let requests = requests.clone();
+        create_resource(
+            || (),
+            move |_| {
+                let state = Rc::new(RefCell::new((None, None)));
+                requests.borrow_mut().push(state.clone());
+                async move { Ok::<_, String>(Deferred { state }.await) }
+            },
+        );
+        scope
+    });
+
+    executor.run_until_stalled();
+    assert_eq!(executor.pending_tasks(), 1);
+
+    scope.dispose();
+    executor.run_until_stalled();
+    assert_eq!(executor.pending_tasks(), 0);
+}
+
+#[test]
+fn results_are_written_in_the_owning_runtime() {
+    let executor = TestExecutor::new();
+
+    let runtime = Runtime::new();
+    let resource = runtime.enter(|| {
+        set_executor(executor.clone());
+        create_resource(|| 1, |id| async move { Ok::<_, String>(id * 10) })
+    });
+
+    // polled while the default runtime is the current one
+    executor.run_until_stalled();
+
+    runtime.enter(|| {
+        assert!(!resource.loading());
+        assert_eq!(resource.get(), Some(10));
+    });
+}
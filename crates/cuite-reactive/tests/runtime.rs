@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use cuite_reactive::{create_effect, create_signal, ReactiveError, Runtime};
+
+#[test]
+fn runtimes_are_independent() {
+    let log: Rc<RefCell<Vec<String>>> = Default::default();
+
+    let first = Runtime::new();
+    let second = Runtime::new();
+    assert_ne!(first.id(), second.id());
+
+    let signals = [&first, &second].map(|runtime| {
+        runtime.enter(|| {
+            let signal = create_signal(0);
+
+            let log = log.clone();
+            let id = runtime.id();
+            create_effect(move |_| log.borrow_mut().push(format!("{id}: {}", signal.get())));
+
+            signal
+        })
+    });
+
+    log.borrow_mut().clear();
+    first.enter(|| signals[0].set(1));
+    second.enter(|| signals[1].set(2));
+
+    assert_eq!(
+        log.borrow().as_slice(),
+        &[format!("{}: 1", first.id()), format!("{}: 2", second.id())]
+    );
+}
+
+#[test]
+fn runtime_mismatch() {
+    let runtime = Runtime::new();
+    let signal = runtime.enter(|| create_signal(1));
+
+    assert!(matches!(
+        signal.try_get(),
+        Err(ReactiveError::RuntimeMismatch { .. })
+    ));
+    assert_eq!(runtime.enter(|| signal.get()), 1);
+}
+
+#[test]
+fn enter_is_nested() {
+    let outer = Runtime::new();
+    let inner = Runtime::new();
+
+    outer.enter(|| {
+        assert_eq!(Runtime::current().id(), outer.id());
+        inner.enter(|| assert_eq!(Runtime::current().id(), inner.id()));
+        assert_eq!(Runtime::current().id(), outer.id());
+    });
+
+    assert_ne!(Runtime::current().id(), outer.id());
+}
+
+#[test]
+fn nodes_are_dropped_with_runtime() {
+    // stored in a signal, so it's `Send` for the `sync` feature
+    let value = Arc::new(());
+
+    let runtime = Runtime::new();
+    let value_copy = value.clone();
+    runtime.enter(|| create_signal(value_copy));
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    drop(runtime);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
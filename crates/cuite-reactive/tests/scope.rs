@@ -0,0 +1,159 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cuite_reactive::{create_effect, create_root, create_signal, on_cleanup};
+
+/// Value which keeps track of the number of its live instances. It's stored
+/// in signals, so it's `Send` for the `sync` feature.
+struct Tracked(Arc<AtomicUsize>);
+
+impl Tracked {
+    fn new(live: &Arc<AtomicUsize>) -> Tracked {
+        live.fetch_add(1, Ordering::Relaxed);
+        Tracked(live.clone())
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn dispose_root() {
+    let live = Arc::new(AtomicUsize::new(0));
+
+    let scope = create_root(|scope| {
+        create_signal(Tracked::new(&live));
+        create_signal(Tracked::new(&live));
+        scope
+    });
+
+    assert_eq!(live.load(Ordering::Relaxed), 2);
+    scope.dispose();
+    assert_eq!(live.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn effect_disposes_children_on_rerun() {
+    let live = Arc::new(AtomicUsize::new(0));
+
+    let scope = create_root(|scope| {
+        let trigger = create_signal(0);
+
+        let live = live.clone();
+        create_effect(move |_| {
+            trigger.get();
+            create_signal(Tracked::new(&live));
+            create_signal(Tracked::new(&live));
+        });
+
+        trigger.set(1);
+        trigger.set(2);
+
+        scope
+    });
+
+    assert_eq!(live.load(Ordering::Relaxed), 2);
+    scope.dispose();
+    assert_eq!(live.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn dispose_nested_effects() {
+    let live = Arc::new(AtomicUsize::new(0));
+    let runs = Rc::new(Cell::new(0));
+
+    let outer = create_signal(0);
+    let inner = create_signal(0);
+
+    let scope = create_root(|scope| {
+        let live = live.clone();
+        let runs = runs.clone();
+        create_effect(move |_| {
+            outer.get();
+
+            let live = live.clone();
+            let runs = runs.clone();
+            create_effect(move |_| {
+                inner.get();
+                runs.set(runs.get() + 1);
+                create_signal(Tracked::new(&live));
+            });
+        });
+
+        scope
+    });
+
+    inner.set(1);
+    assert_eq!(runs.get(), 2);
+    assert_eq!(live.load(Ordering::Relaxed), 1);
+
+    outer.set(1);
+    assert_eq!(runs.get(), 3);
+    assert_eq!(live.load(Ordering::Relaxed), 1);
+
+    scope.dispose();
+    assert_eq!(live.load(Ordering::Relaxed), 0);
+
+    outer.set(2);
+    inner.set(2);
+    assert_eq!(runs.get(), 3);
+}
+
+#[test]
+fn cleanup_on_rerun_and_dispose() {
+    let ops: Rc<RefCell<Vec<String>>> = Default::default();
+
+    let signal = create_signal(0);
+
+    let scope = create_root(|scope| {
+        let ops = ops.clone();
+        create_effect(move |_| {
+            let value = signal.get();
+            ops.borrow_mut().push(format!("run {value}"));
+
+            let ops_copy = ops.clone();
+            on_cleanup(move || ops_copy.borrow_mut().push(format!("first {value}")));
+
+            let ops_copy = ops.clone();
+            on_cleanup(move || ops_copy.borrow_mut().push(format!("second {value}")));
+        });
+
+        scope
+    });
+
+    signal.set(1);
+    scope.dispose();
+    signal.set(2);
+
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &["run 0", "second 0", "first 0", "run 1", "second 1", "first 1"]
+    );
+}
+
+#[test]
+fn cleanup_nested_scopes() {
+    let ops: Rc<RefCell<Vec<&str>>> = Default::default();
+
+    let scope = create_root(|scope| {
+        let ops_copy = ops.clone();
+        on_cleanup(move || ops_copy.borrow_mut().push("root"));
+
+        let ops = ops.clone();
+        create_effect(move |_| {
+            let ops_copy = ops.clone();
+            on_cleanup(move || ops_copy.borrow_mut().push("effect"));
+        });
+
+        scope
+    });
+
+    assert!(ops.borrow().is_empty());
+    scope.dispose();
+    assert_eq!(ops.borrow().as_slice(), &["root", "effect"]);
+}
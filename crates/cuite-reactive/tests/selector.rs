@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_root, create_selector, create_signal, Runtime};
+
+#[test]
+fn only_old_and_new_keys_are_notified() {
+    let ops: Rc<RefCell<Vec<(u32, bool)>>> = Default::default();
+
+    let selected = create_signal(1);
+    let selector = create_selector(move || selected.get());
+
+    for row in 0..100 {
+        let ops = ops.clone();
+        create_effect(move |_| ops.borrow_mut().push((row, selector.selected(&row))));
+    }
+
+    assert_eq!(ops.borrow().len(), 100);
+    assert_eq!(selector.tracked_keys(), 100);
+
+    ops.borrow_mut().clear();
+    selected.set(42);
+    ops.borrow_mut().sort();
+    assert_eq!(ops.borrow().as_slice(), &[(1, false), (42, true)]);
+
+    ops.borrow_mut().clear();
+    selected.set(42);
+    assert!(ops.borrow().is_empty());
+
+    ops.borrow_mut().clear();
+    selected.set(500);
+    assert_eq!(ops.borrow().as_slice(), &[(42, false)]);
+}
+
+#[test]
+fn untracked_reads() {
+    let selected = create_signal("a");
+    let selector = create_selector(move || selected.get());
+
+    assert!(selector.selected(&"a"));
+    assert!(!selector.selected(&"b"));
+    assert_eq!(selector.tracked_keys(), 0);
+
+    selected.set("b");
+    assert!(selector.selected(&"b"));
+}
+
+#[test]
+fn keys_without_readers_are_pruned() {
+    let selected = create_signal(0);
+    let selector = create_selector(move || selected.get());
+
+    let scopes = (0..10)
+        .map(|row| {
+            create_root(|scope| {
+                create_effect(move |_| selector.selected(&row));
+                scope
+            })
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(selector.tracked_keys(), 10);
+
+    scopes[0].dispose();
+    scopes[5].dispose();
+    assert_eq!(selector.tracked_keys(), 8);
+
+    selected.set(5);
+    assert_eq!(selector.tracked_keys(), 8);
+}
+
+#[test]
+fn keys_are_pruned_with_row_churn() {
+    let selected = create_signal(0);
+    let selector = create_selector(move || selected.get());
+    let stats = Runtime::current().stats();
+
+    for row in 0..1000 {
+        let scope = create_root(|scope| {
+            create_effect(move |_| selector.selected(&row));
+            create_effect(move |_| selector.selected(&row));
+            scope
+        });
+
+        assert_eq!(selector.tracked_keys(), 1);
+        scope.dispose();
+    }
+
+    assert_eq!(selector.tracked_keys(), 0);
+    assert_eq!(Runtime::current().stats().triggers, stats.triggers);
+}
+
+#[test]
+fn keys_are_released_when_readers_rerun() {
+    let selected = create_signal(0);
+    let selector = create_selector(move || selected.get());
+    let row = create_signal(1);
+    let ops: Rc<RefCell<Vec<bool>>> = Default::default();
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        let selected = selector.selected(&row.get());
+        ops_copy.borrow_mut().push(selected);
+    });
+    assert_eq!(selector.tracked_keys(), 1);
+
+    for i in 2..100 {
+        row.set(i);
+    }
+    assert_eq!(selector.tracked_keys(), 1);
+
+    ops.borrow_mut().clear();
+    selected.set(99);
+    selected.set(0);
+    assert_eq!(ops.borrow().as_slice(), &[true, false]);
+    assert_eq!(selector.tracked_keys(), 1);
+}
+
+#[test]
+fn disposed_with_owner() {
+    let selected = create_signal(0);
+
+    let (selector, scope) = create_root(|scope| {
+        let selector = create_selector(move || selected.get());
+        create_effect(move |_| selector.selected(&1));
+        (selector, scope)
+    });
+
+    scope.dispose();
+    selected.set(1);
+    assert!(std::panic::catch_unwind(|| selector.selected(&1)).is_err());
+}
@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use cuite_reactive::{create_effect, create_root, create_signal, ReactiveError, Runtime};
+
+#[test]
+fn updates_are_applied_in_a_batch() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let runtime = Runtime::new();
+    let (signal, sender) = runtime.enter(|| {
+        let signal = create_signal(0);
+
+        let log = log.clone();
+        create_effect(move |_| log.lock().unwrap().push(signal.get()));
+
+        (signal, signal.sender())
+    });
+
+    thread::spawn(move || {
+        for i in 1..=3 {
+            assert!(sender.send(i));
+        }
+        assert!(sender.send_update(|v| *v += 10));
+    })
+    .join()
+    .unwrap();
+
+    // nothing is applied until the runtime drains the queue
+    assert_eq!(log.lock().unwrap().as_slice(), &[0]);
+
+    assert_eq!(runtime.drain_external_updates(), 4);
+    assert_eq!(log.lock().unwrap().as_slice(), &[0, 13]);
+    assert_eq!(runtime.enter(|| signal.get()), 13);
+
+    assert_eq!(runtime.drain_external_updates(), 0);
+}
+
+#[test]
+fn sending_to_a_dropped_runtime() {
+    let runtime = Runtime::new();
+    let sender = runtime.enter(|| create_signal(0).sender());
+    drop(runtime);
+
+    assert!(!sender.send(1));
+}
+
+#[test]
+fn sending_to_a_disposed_signal() {
+    let runtime = Runtime::new();
+    let sender = runtime.enter(|| {
+        let (sender, scope) = create_root(|scope| (create_signal(0).sender(), scope));
+        scope.dispose();
+        sender
+    });
+
+    assert!(sender.send(1));
+    assert_eq!(runtime.drain_external_updates(), 1);
+}
+
+#[test]
+fn sender_of_a_foreign_signal() {
+    let runtime = Runtime::new();
+    let signal = runtime.enter(|| create_signal(0));
+
+    assert!(matches!(
+        signal.try_sender(),
+        Err(ReactiveError::RuntimeMismatch { .. })
+    ));
+}
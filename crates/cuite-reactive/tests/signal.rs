@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{
+    create_effect, create_memo, create_signal, create_signal_split, SignalGet, SignalSet,
+    SignalUpdate, SignalWith,
+};
+
+fn double(signal: impl SignalGet<Value = i32>) -> i32 {
+    signal.get() * 2
+}
+
+fn length(signal: impl SignalWith<Value = String>) -> usize {
+    signal.with(|v| v.len())
+}
+
+fn increment(signal: impl SignalUpdate<Value = i32>) {
+    signal.update(|v| *v += 1);
+}
+
+#[test]
+fn split_signal() {
+    let ops: Rc<RefCell<Vec<i32>>> = Default::default();
+
+    let (read, write) = create_signal_split(0);
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        ops_copy.borrow_mut().push(read.get());
+    });
+
+    write.set(1);
+    write.update(|v| *v += 1);
+
+    assert_eq!(ops.borrow().as_slice(), &[0, 1, 2]);
+}
+
+#[test]
+fn read_only_signal() {
+    let signal = create_signal(String::from("abc"));
+    let read = signal.read_only();
+
+    signal.set(String::from("abcd"));
+
+    assert_eq!(read.get(), "abcd");
+    assert_eq!(length(read), 4);
+    assert_eq!(length(signal), 4);
+}
+
+#[test]
+fn interchangeable_signals() {
+    let signal = create_signal(1);
+    let (read, write) = signal.split();
+    let memo = create_memo(move |_| signal.get() + 1);
+
+    assert_eq!(double(signal), 2);
+    assert_eq!(double(read), 2);
+    assert_eq!(double(memo), 4);
+
+    increment(signal);
+    increment(write);
+    assert_eq!(double(memo), 8);
+
+    SignalSet::set(&write, 10);
+    assert_eq!(SignalGet::get(&read), 10);
+}
@@ -0,0 +1,145 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_root, create_store, Store};
+
+#[derive(Debug, Clone, PartialEq, Store)]
+struct Address {
+    city: String,
+    street: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Store)]
+struct User {
+    name: String,
+    age: u32,
+    address: Address,
+}
+
+fn user() -> User {
+    User {
+        name: "Alice".into(),
+        age: 30,
+        address: Address {
+            city: "Paris".into(),
+            street: "Rue de Rivoli".into(),
+        },
+    }
+}
+
+#[test]
+fn field_access() {
+    let store = create_store(user());
+
+    assert_eq!(store.name().get(), "Alice");
+    assert_eq!(store.address().city().get(), "Paris");
+
+    store.address().city().set("Lyon".into());
+    store.age().update(|v| *v += 1);
+
+    assert_eq!(store.get().address.city, "Lyon");
+    assert_eq!(store.get().age, 31);
+}
+
+#[test]
+fn notifications_are_fine_grained() {
+    let ops: Rc<RefCell<Vec<&str>>> = Default::default();
+
+    let store = create_store(user());
+
+    let effect = |label: &'static str, read: Box<dyn Fn()>| {
+        let ops = ops.clone();
+        create_effect(move |_| {
+            read();
+            ops.borrow_mut().push(label);
+        });
+    };
+
+    effect("root", Box::new(move || store.root().track()));
+    effect("name", Box::new(move || store.name().track()));
+    effect("address", Box::new(move || store.address().track()));
+    effect("city", Box::new(move || store.address().city().track()));
+    effect("street", Box::new(move || store.address().street().track()));
+
+    ops.borrow_mut().clear();
+    store.name().set("Bob".into());
+    ops.borrow_mut().sort();
+    assert_eq!(ops.borrow().as_slice(), &["name", "root"]);
+
+    ops.borrow_mut().clear();
+    store.address().city().set("Lyon".into());
+    ops.borrow_mut().sort();
+    assert_eq!(ops.borrow().as_slice(), &["address", "city", "root"]);
+
+    ops.borrow_mut().clear();
+    store.address().set(Address {
+        city: "Nice".into(),
+        street: "Promenade des Anglais".into(),
+    });
+    ops.borrow_mut().sort();
+    assert_eq!(
+        ops.borrow().as_slice(),
+        &["address", "city", "root", "street"]
+    );
+}
+
+#[test]
+fn store_field_handles() {
+    let store = create_store(user());
+    let address = store.address();
+    let city = address.city();
+
+    city.set("Berlin".into());
+    assert_eq!(address.get().city, "Berlin");
+    assert_eq!(store.address().city().get_untracked(), "Berlin");
+}
+
+#[test]
+fn disposed_with_owner() {
+    let ops: Rc<RefCell<Vec<String>>> = Default::default();
+
+    let (store, scope) = create_root(|scope| {
+        let store = create_store(user());
+
+        let ops = ops.clone();
+        create_effect(move |_| ops.borrow_mut().push(store.name().get()));
+
+        (store, scope)
+    });
+
+    scope.dispose();
+    assert!(std::panic::catch_unwind(|| store.name().get()).is_err());
+    assert_eq!(ops.borrow().as_slice(), &["Alice"]);
+}
+
+mod account {
+    use cuite_reactive::{create_store, Store};
+
+    #[derive(Clone, Store)]
+    pub struct Account {
+        pub name: String,
+        password: String,
+    }
+
+    pub fn create_account(name: &str, password: &str) -> Store<Account> {
+        create_store(Account {
+            name: name.into(),
+            password: password.into(),
+        })
+    }
+
+    pub fn check_password(store: Store<Account>, password: &str) -> bool {
+        store.password().with(|v| v == password)
+    }
+}
+
+#[test]
+fn private_fields_are_accessed_in_their_module() {
+    use self::account::{check_password, create_account, AccountStoreFields};
+
+    let store = create_account("Alice", "hunter2");
+    store.name().set("Bob".into());
+
+    assert_eq!(store.name().get(), "Bob");
+    assert!(check_password(store, "hunter2"));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "sync")]
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use cuite_reactive::{create_effect, create_signal, Runtime};
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn writes_from_other_threads_are_queued() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let runtime = Runtime::new();
+    let (signal, sender) = runtime.enter(|| {
+        let signal = create_signal(0);
+
+        let log = log.clone();
+        create_effect(move |_| log.lock().unwrap().push(signal.get()));
+
+        (signal, signal.sender())
+    });
+    assert_send_sync(&signal);
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for i in 1..=3 {
+                assert!(sender.send(i));
+            }
+            assert!(sender.send_update(|v| *v *= 10));
+        });
+    });
+
+    // nothing is applied until the owning runtime drains the queue
+    assert_eq!(log.lock().unwrap().as_slice(), &[0]);
+
+    assert_eq!(runtime.drain_external_updates(), 4);
+    assert_eq!(log.lock().unwrap().as_slice(), &[0, 30]);
+    assert_eq!(runtime.enter(|| signal.get()), 30);
+}
+
+#[test]
+fn signals_can_be_read_on_the_owning_runtime_after_crossing_threads() {
+    let runtime = Runtime::new();
+    let signal = runtime.enter(|| create_signal(String::from("a")));
+
+    let signal = thread::spawn(move || signal).join().unwrap();
+    assert_eq!(runtime.enter(|| signal.get()), "a");
+}
@@ -0,0 +1,249 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cuite_reactive::{
+    batch, create_effect, create_memo, create_signal, create_signal_with_options, Memo, Runtime,
+    Signal, SignalOptions,
+};
+
+use self::common::Rng;
+
+impl Rng {
+    /// Returns up to `max` distinct indices below `n`, at least one.
+    fn pick(&mut self, n: usize, max: usize) -> Vec<usize> {
+        let count = 1 + self.below(max.min(n));
+        let mut picked = Vec::with_capacity(count);
+        while picked.len() < count {
+            let i = self.below(n);
+            if !picked.contains(&i) {
+                picked.push(i);
+            }
+        }
+        picked
+    }
+}
+
+/// Runs of the effects, along with the values they have seen.
+type Runs = Rc<RefCell<Vec<(usize, Vec<u32>)>>>;
+
+#[derive(Clone, Copy)]
+enum Handle {
+    Signal(Signal<u32>),
+    Memo(Memo<u32>),
+}
+
+impl Handle {
+    fn get(&self) -> u32 {
+        match self {
+            Handle::Signal(signal) => signal.get(),
+            Handle::Memo(memo) => memo.get(),
+        }
+    }
+}
+
+/// Value of a memo computed from the values of its sources. Collisions are
+/// frequent, so that memos often stop the propagation of an update.
+fn formula(index: usize, sources: impl IntoIterator<Item = u32>) -> u32 {
+    let sum = sources.into_iter().fold(0, u32::wrapping_add);
+    (sum + index as u32) % 5
+}
+
+/// Random graph of signals, memos and effects. Every node except signals
+/// reads a few of the nodes created before it, which results in many
+/// diamonds.
+struct Graph {
+    /// Sources of the nodes, empty for signals.
+    sources: Vec<Vec<usize>>,
+    handles: Vec<Handle>,
+
+    /// Sources of the effects.
+    effects: Vec<Vec<usize>>,
+
+    /// Runs of the effects during the current update.
+    runs: Runs,
+}
+
+impl Graph {
+    fn new(rng: &mut Rng) -> Graph {
+        let signals = 1 + rng.below(4);
+        let memos = rng.below(16);
+
+        let mut sources = Vec::new();
+        let mut handles = Vec::new();
+
+        for _ in 0..signals {
+            let signal = create_signal_with_options(rng.below(5) as u32, SignalOptions::dedup());
+            sources.push(Vec::new());
+            handles.push(Handle::Signal(signal));
+        }
+
+        for _ in 0..memos {
+            let index = handles.len();
+            let memo_sources = rng.pick(handles.len(), 3);
+            let source_handles = memo_sources.iter().map(|&i| handles[i]).collect::<Vec<_>>();
+
+            let memo = create_memo(move |_| formula(index, source_handles.iter().map(Handle::get)));
+            sources.push(memo_sources);
+            handles.push(Handle::Memo(memo));
+        }
+
+        let runs = Runs::default();
+        let mut effects = Vec::new();
+
+        for effect in 0..1 + rng.below(6) {
+            let effect_sources = rng.pick(handles.len(), 3);
+            let source_handles = effect_sources
+                .iter()
+                .map(|&i| handles[i])
+                .collect::<Vec<_>>();
+
+            let runs = runs.clone();
+            create_effect(move |_| {
+                let values = source_handles.iter().map(Handle::get).collect();
+                runs.borrow_mut().push((effect, values));
+            });
+            effects.push(effect_sources);
+        }
+
+        Graph {
+            sources,
+            handles,
+            effects,
+            runs,
+        }
+    }
+
+    /// Computes the values of all nodes from the values of the signals.
+    fn expected(&self, signals: &[u32]) -> Vec<u32> {
+        let mut values = signals.to_vec();
+        for (index, sources) in self.sources.iter().enumerate().skip(signals.len()) {
+            let value = formula(index, sources.iter().map(|&i| values[i]));
+            values.push(value);
+        }
+        values
+    }
+
+    fn signals(&self) -> Vec<Signal<u32>> {
+        self.handles
+            .iter()
+            .filter_map(|handle| match handle {
+                Handle::Signal(signal) => Some(*signal),
+                Handle::Memo(_) => None,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn random_diamonds() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+
+    for _ in 0..300 {
+        let runtime = Runtime::new();
+        runtime.enter(|| {
+            let graph = Graph::new(&mut rng);
+            let signals = graph.signals();
+
+            let mut values = signals
+                .iter()
+                .map(|s| s.get_untracked())
+                .collect::<Vec<_>>();
+            let mut expected = graph.expected(&values);
+
+            // the initial runs see consistent values
+            for (effect, seen) in graph.runs.take() {
+                let sources = &graph.effects[effect];
+                assert_eq!(
+                    seen,
+                    sources.iter().map(|&i| expected[i]).collect::<Vec<_>>()
+                );
+            }
+
+            for _ in 0..10 {
+                // write one or more signals in a batch
+                let writes = rng.pick(signals.len(), signals.len());
+                let new_values = writes
+                    .iter()
+                    .map(|_| rng.below(5) as u32)
+                    .collect::<Vec<_>>();
+
+                batch(|| {
+                    for (&i, &value) in writes.iter().zip(&new_values) {
+                        signals[i].set(value);
+                        values[i] = value;
+                    }
+                });
+
+                let prev = expected;
+                expected = graph.expected(&values);
+
+                let runs = graph.runs.take();
+                for (effect, sources) in graph.effects.iter().enumerate() {
+                    let effect_runs = runs
+                        .iter()
+                        .filter(|(e, _)| *e == effect)
+                        .collect::<Vec<_>>();
+                    let changed = sources.iter().any(|&i| prev[i] != expected[i]);
+
+                    // an effect runs once iff one of its sources has changed
+                    assert_eq!(effect_runs.len(), changed as usize);
+
+                    // and it sees the final values of all of its sources
+                    for (_, seen) in effect_runs {
+                        let values = sources.iter().map(|&i| expected[i]).collect::<Vec<_>>();
+                        assert_eq!(seen, &values);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn effects_run_after_the_effects_below_them() {
+    let log: Rc<RefCell<Vec<(&str, i32)>>> = Default::default();
+
+    let source = create_signal(0);
+    let output = create_signal(0);
+    let doubled = create_memo(move |_| source.get() * 2);
+
+    // created first, so it would be scheduled first without ordering by height
+    let log_copy = log.clone();
+    create_effect(move |_| {
+        let value = doubled.get() + output.get();
+        log_copy.borrow_mut().push(("upper", value));
+    });
+
+    let log_copy = log.clone();
+    create_effect(move |_| {
+        let value = source.get();
+        output.set(value);
+        log_copy.borrow_mut().push(("lower", value));
+    });
+
+    log.take();
+    source.set(1);
+    assert_eq!(log.take().as_slice(), &[("lower", 1), ("upper", 3)]);
+}
+
+#[test]
+fn sources_read_behind_a_condition_are_not_updated() {
+    let opt = create_signal(Some(1));
+    let is_some = create_memo(move |_| opt.with(Option::is_some));
+    let unwrapped = create_memo(move |_| opt.get().unwrap());
+
+    let log: Rc<RefCell<Vec<Option<i32>>>> = Default::default();
+    let log_copy = log.clone();
+    create_effect(move |_| {
+        let value = is_some.get().then(|| unwrapped.get());
+        log_copy.borrow_mut().push(value);
+    });
+
+    // `unwrapped` is only read while `is_some` is true, so the effect stops
+    // checking its sources as soon as `is_some` changes
+    opt.set(None);
+    opt.set(Some(2));
+    assert_eq!(log.take(), [Some(1), None, Some(2)]);
+}
@@ -0,0 +1,48 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use cuite_reactive::{create_effect, create_signal, on_cleanup, untrack, Signal};
+
+fn sum(signals: &[Signal<i32>]) -> i32 {
+    signals.iter().map(|s| s.get()).sum()
+}
+
+#[test]
+fn untracked_helper() {
+    let ops: Rc<RefCell<Vec<(i32, i32)>>> = Default::default();
+
+    let trigger = create_signal(0);
+    let signals = [create_signal(1), create_signal(2), create_signal(3)];
+
+    let ops_copy = ops.clone();
+    create_effect(move |_| {
+        let total = untrack(|| sum(&signals));
+        ops_copy.borrow_mut().push((trigger.get(), total));
+    });
+
+    signals[0].set(10);
+    signals[2].set(30);
+    trigger.set(1);
+
+    assert_eq!(ops.borrow().as_slice(), &[(0, 6), (1, 42)]);
+}
+
+#[test]
+fn untrack_keeps_scope() {
+    let cleanups = Rc::new(Cell::new(0));
+
+    let trigger = create_signal(0);
+
+    let cleanups_copy = cleanups.clone();
+    create_effect(move |_| {
+        trigger.get();
+
+        let cleanups = cleanups_copy.clone();
+        untrack(|| on_cleanup(move || cleanups.set(cleanups.get() + 1)));
+    });
+
+    trigger.set(1);
+    trigger.set(2);
+
+    assert_eq!(cleanups.get(), 2);
+}